@@ -0,0 +1,520 @@
+//! Derive macros for `oor-nmea0183`, re-exported by it.  The attributes they take are
+//! documented on `oor_nmea0183::field`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Error, Fields,
+    GenericArgument, Ident, LitInt, LitStr, Path, PathArguments, Type,
+};
+
+/// The members every sentence struct has, they are filled from the sentence header rather than
+/// from its fields.
+const HEADER: [&str; 3] = ["talker_id", "message_id", "tag_block"];
+
+/// Implements a `decode` function taking a `DecodeContext`, `TryFrom<Nmea>`, `NmeaSentence` and
+/// `SentenceMeta` for a sentence struct.
+#[proc_macro_derive(NmeaSentence, attributes(nmea))]
+pub fn derive_nmea_sentence(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_sentence(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implements `NmeaField` for an enum whose variants are written as fixed strings.
+#[proc_macro_derive(NmeaEnum, attributes(nmea))]
+pub fn derive_nmea_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_enum(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The unit of a member, a `uom` unit it is converted with or only the name of the unit the
+/// field is in.
+enum Unit {
+    Quantity(Path),
+    Name(LitStr),
+}
+
+impl Unit {
+    fn quantity(&self) -> Option<&Path> {
+        match self {
+            Unit::Quantity(unit) => Some(unit),
+            Unit::Name(_) => None,
+        }
+    }
+}
+
+/// A struct member decoded from a field of the sentence.
+struct FieldSpec {
+    ident: Ident,
+    index: usize,
+    /// The type of the member, or the type inside the `Option` or `Vec` for optional and
+    /// repeated members.
+    ty: Type,
+    nullable: bool,
+    unit: Option<Unit>,
+    /// Whether the sentence can end before this field.
+    optional: bool,
+    /// Whether an `Option` member is `None` only for an invalid value that was dropped, the
+    /// field itself cannot be empty.
+    non_empty: bool,
+    /// Whether this field and every field after it are decoded into a `Vec`.
+    repeated: bool,
+}
+
+fn nmea_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("nmea"))
+}
+
+/// The `T` of an `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    generic_inner(ty, "Option")
+}
+
+/// The `T` of a `Vec<T>`.
+fn vec_inner(ty: &Type) -> Option<&Type> {
+    generic_inner(ty, "Vec")
+}
+
+/// The `T` of a `wrapper<T>`.
+fn generic_inner<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn expand_sentence(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let mut manufacturer: Option<LitStr> = None;
+    let mut message_id: Option<LitStr> = None;
+    for attr in nmea_attrs(&input.attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("manufacturer") {
+                manufacturer = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("message_id") {
+                message_id = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("expected `manufacturer` or `message_id`"));
+            }
+            Ok(())
+        })?;
+    }
+    let message_id = message_id.ok_or_else(|| {
+        Error::new(
+            input.ident.span(),
+            "missing `#[nmea(message_id = \"...\")]` on the sentence struct",
+        )
+    })?;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.span(),
+            "`NmeaSentence` can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(Error::new(
+            input.span(),
+            "`NmeaSentence` needs a struct with named members",
+        ));
+    };
+
+    let mut specs = Vec::new();
+    let mut next_index = 0;
+    for member in &named.named {
+        let ident = member.ident.clone().unwrap();
+        if HEADER.iter().any(|header| ident == header) {
+            continue;
+        }
+        if let Some(repeated) = specs.iter().find(|spec: &&FieldSpec| spec.repeated) {
+            return Err(Error::new(
+                member.span(),
+                format!(
+                    "the `repeated` member `{}` has to be the last one",
+                    repeated.ident
+                ),
+            ));
+        }
+        let mut index = next_index;
+        let mut unit = None;
+        let mut optional = false;
+        let mut non_empty = false;
+        let mut repeated = false;
+        for attr in nmea_attrs(&member.attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("index") {
+                    index = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+                } else if meta.path.is_ident("unit") {
+                    let value = meta.value()?;
+                    unit = Some(if value.peek(LitStr) {
+                        Unit::Name(value.parse()?)
+                    } else {
+                        Unit::Quantity(value.parse()?)
+                    });
+                } else if meta.path.is_ident("optional") {
+                    optional = true;
+                } else if meta.path.is_ident("non_empty") {
+                    non_empty = true;
+                } else if meta.path.is_ident("repeated") {
+                    repeated = true;
+                } else {
+                    return Err(meta
+                        .error("expected `index`, `unit`, `optional`, `non_empty` or `repeated`"));
+                }
+                Ok(())
+            })?;
+        }
+        if let Some(other) = specs.iter().find(|spec: &&FieldSpec| spec.index == index) {
+            return Err(Error::new(
+                member.span(),
+                format!("field {} is already decoded into `{}`", index, other.ident),
+            ));
+        }
+        let (ty, nullable) = match (repeated, option_inner(&member.ty)) {
+            (true, _) => match vec_inner(&member.ty) {
+                Some(inner) => (inner.clone(), false),
+                None => {
+                    return Err(Error::new(
+                        member.span(),
+                        "a `repeated` field must be decoded into a `Vec`",
+                    ))
+                }
+            },
+            (false, Some(inner)) => (inner.clone(), true),
+            (false, None) => (member.ty.clone(), false),
+        };
+        if optional && !nullable && !repeated {
+            return Err(Error::new(
+                member.span(),
+                "an `optional` field must be decoded into an `Option` or be `repeated`",
+            ));
+        }
+        if non_empty && !nullable {
+            return Err(Error::new(
+                member.span(),
+                "a `non_empty` field must be decoded into an `Option`",
+            ));
+        }
+        next_index = index + 1;
+        specs.push(FieldSpec {
+            ident,
+            index,
+            ty,
+            nullable,
+            unit,
+            optional,
+            non_empty,
+            repeated,
+        });
+    }
+    for header in HEADER {
+        if !named
+            .named
+            .iter()
+            .any(|member| member.ident.as_ref().unwrap() == header)
+        {
+            return Err(Error::new(
+                input.ident.span(),
+                format!("a sentence struct needs a `{}` member", header),
+            ));
+        }
+    }
+
+    let krate = quote!(::oor_nmea0183);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let min_fields = specs
+        .iter()
+        .filter(|spec| !spec.optional)
+        .map(|spec| spec.index + 1)
+        .max()
+        .unwrap_or(0);
+    let min_fields_u16 = u16::try_from(min_fields)
+        .map_err(|_| Error::new(input.span(), "too many fields for a sentence"))?;
+    let repeated = specs.iter().find(|spec| spec.repeated);
+    let fixed = specs.iter().filter(|spec| !spec.repeated);
+    let mut len = fixed.map(|spec| spec.index + 1).max().unwrap_or(0);
+    if let Some(repeated) = repeated {
+        if repeated.index < len {
+            return Err(Error::new(
+                repeated.ident.span(),
+                "a `repeated` member has to decode the last fields",
+            ));
+        }
+        len = repeated.index;
+    }
+
+    let idents: Vec<_> = specs.iter().map(|spec| &spec.ident).collect();
+    let decoders = specs.iter().map(|spec| {
+        let FieldSpec {
+            ident, index, ty, ..
+        } = spec;
+        let field_name = ident.to_string();
+        let quantity = spec.unit.as_ref().and_then(Unit::quantity);
+        let value_ty = match quantity {
+            Some(_) => quote!(<#ty as #krate::field::Quantity>::Value),
+            None => quote!(#ty),
+        };
+        let value = match quantity {
+            Some(unit) => quote!(<#ty>::new::<#unit>(value)),
+            None => quote!(value),
+        };
+        let required = quote!(#krate::field::required::<#value_ty>(&nmea, #index, #field_name));
+        if spec.repeated {
+            quote! {
+                let mut #ident = ::core::default::Default::default();
+                for index in #index..nmea.fields.len() {
+                    let value =
+                        #krate::field::required::<#value_ty>(&nmea, index, #field_name)?;
+                    #krate::storage::TryPush::try_push(&mut #ident, #value)?;
+                }
+            }
+        } else if spec.non_empty {
+            quote! {
+                let #ident = match nmea.fields.get(#index) {
+                    ::core::option::Option::Some(_) => ctx.recover(#required)?,
+                    ::core::option::Option::None => ::core::option::Option::None,
+                }
+                .map(|value| #value);
+            }
+        } else if spec.nullable {
+            quote! {
+                let #ident = ctx
+                    .optional::<#value_ty>(&nmea, #index, #field_name)?
+                    .map(|value| #value);
+            }
+        } else {
+            quote! {
+                let value = #required?;
+                let #ident = #value;
+            }
+        }
+    });
+    let encoded = |spec: &FieldSpec| match spec.unit.as_ref().and_then(Unit::quantity) {
+        Some(unit) => quote!(&value.get::<#unit>()),
+        None => quote!(value),
+    };
+    let encoders = specs.iter().filter(|spec| !spec.repeated).map(|spec| {
+        let FieldSpec { ident, index, .. } = spec;
+        let value = encoded(spec);
+        if spec.nullable {
+            quote! {
+                if let Some(value) = &self.#ident {
+                    fields[#index] = #krate::field::NmeaField::encode_field(#value);
+                }
+            }
+        } else {
+            quote! {
+                let value = &self.#ident;
+                fields[#index] = #krate::field::NmeaField::encode_field(#value);
+            }
+        }
+    });
+    let fields = match repeated {
+        Some(spec) => {
+            let ident = &spec.ident;
+            let value = encoded(spec);
+            quote! {
+                fields.into_iter().chain(
+                    self.#ident
+                        .iter()
+                        .map(|value| #krate::field::NmeaField::encode_field(#value)),
+                )
+            }
+        }
+        None => quote!(fields),
+    };
+    let metadata = specs.iter().map(|spec| {
+        let FieldSpec {
+            ident,
+            index,
+            ty,
+            optional,
+            repeated,
+            ..
+        } = spec;
+        let field_name = ident.to_string();
+        let value_ty = match spec.unit.as_ref().and_then(Unit::quantity) {
+            Some(_) => quote!(<#ty as #krate::field::Quantity>::Value),
+            None => quote!(#ty),
+        };
+        let unit = match &spec.unit {
+            Some(Unit::Quantity(unit)) => {
+                let unit_name = unit.segments.last().unwrap().ident.to_string();
+                quote!(::core::option::Option::Some(#unit_name))
+            }
+            Some(Unit::Name(unit)) => quote!(::core::option::Option::Some(#unit)),
+            None => quote!(::core::option::Option::None),
+        };
+        let nullable = spec.nullable && !spec.non_empty;
+        let option = spec.nullable;
+        let required = !optional;
+        quote! {
+            #krate::field::FieldMeta {
+                name: #field_name,
+                index: #index,
+                ty: <#value_ty as #krate::field::NmeaField>::TYPE,
+                unit: #unit,
+                nullable: #nullable,
+                option: #option,
+                required: #required,
+                repeated: #repeated,
+            }
+        }
+    });
+    let manufacturer = match manufacturer {
+        Some(manufacturer) => quote!(::core::option::Option::Some(#manufacturer)),
+        None => quote!(::core::option::Option::None),
+    };
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Decodes the sentence, `ctx` decides whether an invalid value of an `Option` member
+            /// rejects the sentence.
+            #[allow(unused_variables)]
+            pub fn decode(
+                nmea: #krate::Nmea<'_>,
+                ctx: &mut #krate::sentences::DecodeContext,
+            ) -> ::core::result::Result<Self, #krate::sentences::error::ParseNMEA0183Error> {
+                if nmea.fields.len() < #min_fields {
+                    return ::core::result::Result::Err(
+                        #krate::sentences::error::ParseNMEA0183Error::MissingFields(#min_fields_u16),
+                    );
+                }
+                #(#decoders)*
+                ::core::result::Result::Ok(#name {
+                    talker_id: nmea.talker_id,
+                    message_id: ::core::convert::From::from(#message_id),
+                    tag_block: nmea.tag_block,
+                    #(#idents,)*
+                })
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<#krate::Nmea<'_>> for #name #ty_generics #where_clause {
+            type Error = #krate::sentences::error::ParseNMEA0183Error;
+
+            fn try_from(nmea: #krate::Nmea<'_>) -> ::core::result::Result<Self, Self::Error> {
+                #name::decode(nmea, &mut ::core::default::Default::default())
+            }
+        }
+
+        impl #impl_generics #krate::sentences::NmeaSentence for #name #ty_generics #where_clause {
+            fn talker_id(&self) -> &#krate::talker::TalkerId {
+                &self.talker_id
+            }
+
+            fn message_id(&self) -> #krate::storage::Cow<'_, str> {
+                #krate::storage::Cow::Borrowed(&self.message_id)
+            }
+
+            fn tag_block(&self) -> ::core::option::Option<&#krate::tag_block::TagBlock> {
+                self.tag_block.as_ref()
+            }
+
+            fn to_nmea(
+                &self,
+            ) -> ::core::result::Result<
+                #krate::Nmea<'static>,
+                #krate::sentences::error::ParseNMEA0183Error,
+            > {
+                #[allow(unused_mut)]
+                let mut fields: [#krate::storage::Cow<'static, str>; #len] =
+                    ::core::array::from_fn(|_| #krate::storage::Cow::Borrowed(""));
+                #(#encoders)*
+                #krate::field::encode(self, #fields)
+            }
+        }
+
+        impl #impl_generics #krate::field::SentenceMeta for #name #ty_generics #where_clause {
+            const MANUFACTURER: ::core::option::Option<&'static str> = #manufacturer;
+            const MESSAGE_ID: &'static str = #message_id;
+            const FIELDS: &'static [#krate::field::FieldMeta] = &[#(#metadata),*];
+        }
+    })
+}
+
+fn expand_enum(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new(
+            input.span(),
+            "`NmeaEnum` can only be derived for enums",
+        ));
+    };
+
+    let mut variants = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(
+                variant.span(),
+                "`NmeaEnum` variants cannot hold data",
+            ));
+        }
+        let mut value = None;
+        for attr in nmea_attrs(&variant.attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("value") {
+                    value = Some(meta.value()?.parse::<LitStr>()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `value`"))
+                }
+            })?;
+        }
+        let value = value.ok_or_else(|| {
+            Error::new(
+                variant.span(),
+                "missing `#[nmea(value = \"...\")]` on the variant",
+            )
+        })?;
+        variants.push((&variant.ident, value));
+    }
+
+    let krate = quote!(::oor_nmea0183);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let values = variants.iter().map(|(_, value)| value);
+    let decode = variants
+        .iter()
+        .map(|(ident, value)| quote!(#value => ::core::result::Result::Ok(#name::#ident),));
+    let encode = variants
+        .iter()
+        .map(|(ident, value)| quote!(#name::#ident => #value,));
+
+    Ok(quote! {
+        impl #impl_generics #krate::field::NmeaField for #name #ty_generics #where_clause {
+            const TYPE: #krate::field::FieldType = #krate::field::FieldType::Enum(&[#(#values),*]);
+
+            fn decode_field(
+                field: &str,
+            ) -> ::core::result::Result<Self, #krate::sentences::error::FieldErrorKind> {
+                match field {
+                    #(#decode)*
+                    _ => ::core::result::Result::Err(
+                        #krate::sentences::error::FieldErrorKind::InvalidEnumerator,
+                    ),
+                }
+            }
+
+            fn encode_field(&self) -> #krate::storage::Cow<'static, str> {
+                #krate::storage::Cow::Borrowed(match self {
+                    #(#encode)*
+                })
+            }
+        }
+    })
+}
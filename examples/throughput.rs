@@ -0,0 +1,77 @@
+//! Compares how fast the pest grammar and the byte scanner behind `NmeaParser` split sentences.
+//!
+//! ```text
+//! cargo run --release --example throughput [log file]
+//! ```
+//!
+//! Without a log file a mix of the sentences decoded by this crate is used.
+
+use oor_nmea0183::{checksum, NmeaParser, NmeaPest, Rule};
+use pest::Parser;
+use std::{
+    env, fs,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const SAMPLES: [&str; 6] = [
+    "$WIMWV,049,R,000.03,N,A*03",
+    "$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56",
+    "$PGILT,A,+00,D,+01,D,+1,TILT*35",
+    "$PSVDY,-0.210,-0.116,9.825,-0.0044,0.0011,-0.0044,217.3,0.6,-1.4,0.021,-0.012,9.828,703*6C",
+    "$PSVSS,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000*55",
+    "\\c:1700000000,s:GP01*2C\\$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56",
+];
+
+/// Splits a sentence the way `NmeaParser` did before it had its own scanner.
+fn split_with_pest(line: &str) -> Option<(usize, u8)> {
+    let parsed = NmeaPest::parse(Rule::NMEA, line).ok()?.next()?;
+    let mut fields = 0;
+    let mut body = (0, line.len());
+    for pair in parsed.into_inner() {
+        match pair.as_rule() {
+            Rule::start_delimiter => body.0 = pair.as_span().end(),
+            Rule::fields => fields = pair.as_str().split(',').count(),
+            Rule::checksum => body.1 = pair.as_span().start(),
+            _ => {}
+        }
+    }
+    Some((fields, checksum(&line[body.0..body.1])))
+}
+
+fn measure(name: &str, lines: &[&str], mut split: impl FnMut(&str) -> bool) -> Duration {
+    let bytes: usize = lines.iter().map(|line| line.len()).sum();
+    let start = Instant::now();
+    let mut rounds = 0;
+    while start.elapsed() < Duration::from_secs(2) {
+        for line in lines {
+            black_box(split(black_box(line)));
+        }
+        rounds += 1;
+    }
+    let elapsed = start.elapsed() / rounds;
+    println!(
+        "{:>8}: {:>8.0} sentences/s, {:>7.1} MB/s",
+        name,
+        lines.len() as f64 / elapsed.as_secs_f64(),
+        bytes as f64 / elapsed.as_secs_f64() / 1e6,
+    );
+    elapsed
+}
+
+fn main() {
+    let log = env::args()
+        .nth(1)
+        .map(|path| fs::read_to_string(path).unwrap());
+    let lines: Vec<&str> = match &log {
+        Some(log) => log.lines().map(str::trim).collect(),
+        None => SAMPLES.iter().copied().cycle().take(10_000).collect(),
+    };
+
+    let pest = measure("pest", &lines, |line| split_with_pest(line).is_some());
+    let scanner = measure("scanner", &lines, |line| NmeaParser::to_nmea(line).is_ok());
+    println!(
+        "scanner is {:.1}x faster",
+        pest.as_secs_f64() / scanner.as_secs_f64()
+    );
+}
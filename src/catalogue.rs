@@ -0,0 +1,404 @@
+//! The sentences this crate decodes and their fields, for building user interfaces and storage
+//! schemas from.
+//!
+//! Only the sentences of the enabled sentence families are listed.  Queries and sentences
+//! decoded by a [`crate::registry::SentenceRegistry`] are not.
+//!
+//! [`json_schema`] describes every sentence as the JSON object it is serialized as with the
+//! `serde` feature, with its variant name as `type` and a property per field:
+//!
+//! - Members of nested structs, named `member.name` in the [`FieldMeta`], are grouped in an
+//!   object property named after the member.
+//! - Repeated fields are an array property, of objects if there are several of them.
+//! - Fields decoded into an `Option` may be `null`, as they are when the field is empty or
+//!   invalid, and the others are listed as `required`.
+//! - Each property has the index of its field in `x-nmea-index` and its unit, if it has one,
+//!   in `x-nmea-unit`.
+
+use crate::field::{FieldMeta, FieldType, SentenceMeta};
+use core::fmt::{self, Write};
+
+/// A sentence this crate decodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SentenceInfo {
+    /// The [`crate::Sentence`] variant the sentence is decoded into.
+    pub name: &'static str,
+    /// The manufacturer mnemonic of a proprietary sentence.
+    pub manufacturer: Option<&'static str>,
+    pub message_id: &'static str,
+    pub fields: &'static [FieldMeta],
+}
+
+impl SentenceInfo {
+    pub const fn of<T: SentenceMeta>(name: &'static str) -> Self {
+        SentenceInfo {
+            name,
+            manufacturer: T::MANUFACTURER,
+            message_id: T::MESSAGE_ID,
+            fields: T::FIELDS,
+        }
+    }
+
+    /// The address of the sentence without the talker ID, e.g. `GGA` or `PSVDY`.
+    pub fn address(&self) -> impl fmt::Display + '_ {
+        struct Address<'a>(&'a SentenceInfo);
+
+        impl fmt::Display for Address<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                if let Some(manufacturer) = self.0.manufacturer {
+                    write!(f, "P{}", manufacturer)?;
+                }
+                f.write_str(self.0.message_id)
+            }
+        }
+
+        Address(self)
+    }
+
+    /// The field at `index`, the repeated fields at the end of a sentence describe every field
+    /// after them in turn.
+    pub fn field(&self, index: usize) -> Option<&'static FieldMeta> {
+        let fields = self.fields;
+        match fields.iter().position(|field| field.repeated) {
+            Some(start) if index >= fields[start].index => {
+                let repeated = &fields[start..];
+                Some(&repeated[(index - fields[start].index) % repeated.len()])
+            }
+            _ => fields.iter().find(|field| field.index == index),
+        }
+    }
+}
+
+const SENTENCES: &[SentenceInfo] = &[
+    #[cfg(feature = "wind")]
+    SentenceInfo::of::<crate::sentences::mwv::Mwv>("Mwv"),
+    #[cfg(feature = "transducer")]
+    SentenceInfo::of::<crate::sentences::xdr::Xdr>("Xdr"),
+    #[cfg(feature = "gnss")]
+    SentenceInfo::of::<crate::sentences::gga::Gga>("Gga"),
+    #[cfg(feature = "heading")]
+    SentenceInfo::of::<crate::sentences::hdm::Hdm>("Hdm"),
+    #[cfg(feature = "heading")]
+    SentenceInfo::of::<crate::sentences::hdt::Hdt>("Hdt"),
+    #[cfg(feature = "gill")]
+    SentenceInfo::of::<crate::sentences::pgilt::Gilt>("Gilt"),
+    #[cfg(feature = "seaview")]
+    SentenceInfo::of::<crate::sentences::seaview::psvdy::Svdy>("Svdy"),
+    #[cfg(feature = "seaview")]
+    SentenceInfo::of::<crate::sentences::seaview::psvss::Svss>("Svss"),
+    #[cfg(feature = "seaview")]
+    SentenceInfo::of::<crate::sentences::seaview::psvsd::Svsd>("Svsd"),
+    #[cfg(feature = "seaview")]
+    SentenceInfo::of::<crate::sentences::seaview::psvst::Svst>("Svst"),
+    #[cfg(feature = "seaview")]
+    SentenceInfo::of::<crate::sentences::seaview::psvsi::Svsi>("Svsi"),
+    #[cfg(feature = "seaview")]
+    SentenceInfo::of::<crate::sentences::seaview::psvsv::Svsv>("Svsv"),
+    #[cfg(feature = "seaview")]
+    SentenceInfo::of::<crate::sentences::seaview::psvsh::Svsh>("Svsh"),
+    #[cfg(feature = "seaview")]
+    SentenceInfo::of::<crate::sentences::seaview::psvsw::Svsw>("Svsw"),
+];
+
+/// Every sentence decoded by [`crate::Sentence::decode`].
+pub fn sentences() -> &'static [SentenceInfo] {
+    SENTENCES
+}
+
+/// The sentence with `message_id`, from `manufacturer` for proprietary sentences.
+pub fn find(manufacturer: Option<&str>, message_id: &str) -> Option<&'static SentenceInfo> {
+    SENTENCES
+        .iter()
+        .find(|info| info.manufacturer == manufacturer && info.message_id == message_id)
+}
+
+/// The catalogue as a JSON Schema (draft 2020-12), write it out with [`fmt::Display`].
+pub fn json_schema() -> impl fmt::Display {
+    JsonSchema
+}
+
+struct JsonSchema;
+
+impl fmt::Display for JsonSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(
+            "{\"$schema\":\"https://json-schema.org/draft/2020-12/schema\",\
+             \"title\":\"NMEA0183 sentences\",\"oneOf\":[",
+        )?;
+        for (i, info) in SENTENCES.iter().enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+            write!(f, "{{\"$ref\":\"#/$defs/{}\"}}", info.address())?;
+        }
+        f.write_str("],\"$defs\":{")?;
+        for (i, info) in SENTENCES.iter().enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+            write!(f, "\"{}\":{{\"title\":\"{}\",", info.address(), info.name)?;
+            write_object(f, info.fields, Some(info.name), false)?;
+            f.write_char('}')?;
+        }
+        f.write_str("}}")
+    }
+}
+
+/// A property of the object a sentence is described as.
+enum Property {
+    Field(&'static FieldMeta),
+    /// The fields decoded into a member of a nested struct, with the name of the member.
+    Group(&'static str, &'static [FieldMeta]),
+}
+
+/// The member a field is grouped under, `readings` for `readings.name`.
+fn group(field: &FieldMeta) -> Option<&'static str> {
+    field.name.split_once('.').map(|(group, _)| group)
+}
+
+/// The properties of an object holding `fields`, the fields of a group are next to each other.
+/// The fields of a `nested` group are all properties of the object it is described as.
+fn properties(fields: &'static [FieldMeta], nested: bool) -> impl Iterator<Item = Property> {
+    let mut rest = fields;
+    core::iter::from_fn(move || {
+        let (field, _) = rest.split_first()?;
+        let Some(name) = group(field).filter(|_| !nested) else {
+            rest = &rest[1..];
+            return Some(Property::Field(field));
+        };
+        let len = rest
+            .iter()
+            .position(|field| group(field) != Some(name))
+            .unwrap_or(rest.len());
+        let (grouped, tail) = rest.split_at(len);
+        rest = tail;
+        Some(Property::Group(name, grouped))
+    })
+}
+
+/// The members every sentence struct starts with, as written by serde.
+const HEADER: &str = "\"talker_id\":{\"type\":\"string\"},\"message_id\":{\"type\":\"string\"},\
+                      \"tag_block\":{\"type\":[\"object\",\"null\"]}";
+
+/// Whether a group is decoded into an `Option`, which it is if all of its fields are.
+fn optional(grouped: &[FieldMeta]) -> bool {
+    grouped.iter().all(|field| field.option)
+}
+
+/// Writes the `type`, `properties` and `required` of an object holding `fields`, which are the
+/// fields of the `sentence` with that variant name, or of a group if there is none.
+fn write_object(
+    f: &mut impl Write,
+    fields: &'static [FieldMeta],
+    sentence: Option<&str>,
+    nullable: bool,
+) -> fmt::Result {
+    let nested = sentence.is_none();
+    if nullable {
+        f.write_str("\"type\":[\"object\",\"null\"],\"properties\":{")?;
+    } else {
+        f.write_str("\"type\":\"object\",\"properties\":{")?;
+    }
+    if let Some(name) = sentence {
+        write!(f, "\"type\":{{\"const\":\"{}\"}},{}", name, HEADER)?;
+    }
+    for (i, property) in properties(fields, nested).enumerate() {
+        if i > 0 || !nested {
+            f.write_char(',')?;
+        }
+        match property {
+            Property::Field(field) => {
+                write!(f, "\"{}\":", name(field))?;
+                if field.repeated && !nested {
+                    f.write_str("{\"type\":\"array\",\"items\":")?;
+                    write_field(f, field)?;
+                    f.write_char('}')?;
+                } else {
+                    write_field(f, field)?;
+                }
+            }
+            Property::Group(name, grouped) => {
+                write!(f, "\"{}\":", name)?;
+                if grouped.iter().any(|field| field.repeated) {
+                    f.write_str("{\"type\":\"array\",\"items\":{")?;
+                    write_object(f, grouped, None, optional(grouped))?;
+                    f.write_str("}}")?;
+                } else {
+                    f.write_char('{')?;
+                    write_object(f, grouped, None, optional(grouped))?;
+                    f.write_char('}')?;
+                }
+            }
+        }
+    }
+    f.write_str("},\"required\":[")?;
+    let header = ["type", "talker_id", "message_id", "tag_block"];
+    let required = properties(fields, nested).filter_map(|property| match property {
+        Property::Field(field) if (field.repeated && !nested) || !field.option => Some(name(field)),
+        Property::Group(name, grouped)
+            if grouped.iter().any(|field| field.repeated) || !optional(grouped) =>
+        {
+            Some(name)
+        }
+        _ => None,
+    });
+    let header = header.into_iter().filter(|_| !nested);
+    for (i, name) in header.chain(required).enumerate() {
+        if i > 0 {
+            f.write_char(',')?;
+        }
+        write!(f, "\"{}\"", name)?;
+    }
+    f.write_char(']')
+}
+
+/// The name of a field within its group.
+fn name(field: &FieldMeta) -> &'static str {
+    field.name.rsplit('.').next().unwrap_or(field.name)
+}
+
+fn write_field(f: &mut impl Write, field: &FieldMeta) -> fmt::Result {
+    f.write_char('{')?;
+    match field.ty {
+        FieldType::Enum(values) => {
+            f.write_str("\"enum\":[")?;
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    f.write_char(',')?;
+                }
+                write!(f, "\"{}\"", value)?;
+            }
+            if field.option {
+                f.write_str(",null")?;
+            }
+            f.write_char(']')?;
+        }
+        ty => {
+            let ty = match ty {
+                FieldType::Integer => "integer",
+                FieldType::Float => "number",
+                _ => "string",
+            };
+            if field.option {
+                write!(f, "\"type\":[\"{}\",\"null\"]", ty)?;
+            } else {
+                write!(f, "\"type\":\"{}\"", ty)?;
+            }
+        }
+    }
+    write!(f, ",\"x-nmea-index\":{}", field.index)?;
+    if let Some(unit) = field.unit {
+        write!(f, ",\"x-nmea-unit\":\"{}\"", unit)?;
+    }
+    f.write_char('}')
+}
+
+#[cfg(all(test, feature = "std", feature = "all-sentences"))]
+mod tests {
+    use super::*;
+    use crate::storage::ToString;
+
+    #[test]
+    fn test_find() {
+        assert_eq!(sentences().len(), 14);
+
+        let gga = find(None, "GGA").unwrap();
+        assert_eq!(gga.name, "Gga");
+        assert_eq!(gga.address().to_string(), "GGA");
+
+        let svdy = find(Some("SVD"), "Y").unwrap();
+        assert_eq!(svdy.name, "Svdy");
+        assert_eq!(svdy.address().to_string(), "PSVDY");
+        assert_eq!(svdy.fields[0].unit, Some("meter_per_second_squared"));
+
+        let xdr = find(None, "XDR").unwrap();
+        assert_eq!(xdr.field(1).unwrap().name, "readings.reading");
+        assert_eq!(xdr.field(6).unwrap().name, "readings.units");
+        assert_eq!(gga.field(13).unwrap().index, 13);
+        assert_eq!(gga.field(14), None);
+
+        assert_eq!(find(None, "Y"), None);
+        assert_eq!(find(Some("GIL"), "GGA"), None);
+    }
+
+    #[test]
+    fn test_json_schema() {
+        let schema = json_schema().to_string();
+        assert!(schema.starts_with(
+            "{\"$schema\":\"https://json-schema.org/draft/2020-12/schema\",\
+             \"title\":\"NMEA0183 sentences\",\"oneOf\":[{\"$ref\":\"#/$defs/MWV\"},"
+        ));
+        assert!(schema.contains(
+            "\"HDT\":{\"title\":\"Hdt\",\"type\":\"object\",\"properties\":{\
+             \"type\":{\"const\":\"Hdt\"},\"talker_id\":{\"type\":\"string\"},\
+             \"message_id\":{\"type\":\"string\"},\"tag_block\":{\"type\":[\"object\",\"null\"]},\
+             \"heading\":{\"type\":[\"number\",\"null\"],\"x-nmea-index\":0},\
+             \"heading_type\":{\"enum\":[\"M\",\"T\",null],\"x-nmea-index\":1}},\
+             \"required\":[\"type\",\"talker_id\",\"message_id\",\"tag_block\"]}"
+        ));
+        assert!(schema.contains(
+            "\"XDR\":{\"title\":\"Xdr\",\"type\":\"object\",\"properties\":{\
+             \"type\":{\"const\":\"Xdr\"},\"talker_id\":{\"type\":\"string\"},\
+             \"message_id\":{\"type\":\"string\"},\"tag_block\":{\"type\":[\"object\",\"null\"]},\
+             \"readings\":{\"type\":\"array\",\"items\":{\"type\":[\"object\",\"null\"],\"properties\":{\
+             \"type\":{\"type\":[\"string\",\"null\"],\"x-nmea-index\":0},"
+        ));
+        assert!(schema.contains(
+            "\"bin\":{\"type\":\"object\",\"properties\":{\
+             \"period\":{\"type\":\"number\",\"x-nmea-index\":0,\"x-nmea-unit\":\"second\"},"
+        ));
+        // Lenient decoding leaves an invalid fix quality `None`, though it cannot be empty.
+        assert!(schema.contains(
+            "\"fix_quality\":{\"enum\":[\"0\",\"1\",\"2\",\"3\",\"4\",\"5\",\"6\",null],\
+             \"x-nmea-index\":5},"
+        ));
+        assert!(schema.contains(
+            "\"z_orientation\":{\"enum\":[\"+1\",\"-1\",null],\"x-nmea-index\":5},\
+             \"sensor_name\":{\"type\":[\"string\",\"null\"],\"x-nmea-index\":6}},\
+             \"required\":[\"type\",\"talker_id\",\"message_id\",\"tag_block\"]}"
+        ));
+        assert_eq!(
+            schema.matches('{').count(),
+            schema.matches('}').count(),
+            "{}",
+            schema
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_schema_describes_serde() {
+        let schema: serde_json::Value = serde_json::from_str(&json_schema().to_string()).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        let lines = [
+            "$WIMWV,049,R,000.03,N,A*03",
+            "$WIMWV,,,,,V",
+            "$WIXDR,C,+023.1,C,TEMP,P,0.9989,B,PRESS,X,040,P,RH",
+            "$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56",
+            "$GPGGA,,,,,,9,,,,,,,,",
+            "$HCHDM,238.5,M",
+            "$HEHDT,,",
+            "$PGILT,A,+00,D,+01,D,+1,TILT*35",
+            "$PGILT,A,,D,,D,+2,",
+            "$PSVDY,-0.210,-0.116,9.825,-0.0044,0.0011,-0.0044,217.3,0.6,-1.4,0.021,-0.012,9.828,703*6C",
+            "$PSVDY,,,,,,,,,,,,,",
+            "$PSVSS,1,2,3,4,5,6,7,8,9,10,11,12,13,14",
+            "$PSVSD,128.0000,0.0000,0.0000,0.0000",
+            "$PSVST,2020-10-02 16:04:53*58",
+            "$PSVST,",
+            "$PSVSI,WINDMILL*73",
+            "$PSVSV,0.000,0.024,0.037,-0.663*7C",
+            "\\s:GP01,c:1700000000*2C\\$PSVSH,Period,ESmag",
+            "$PSVSW,1,2",
+        ];
+        for line in lines {
+            let (sentence, _) = crate::NmeaParser::parse_lenient(line).unwrap();
+            let value = serde_json::to_value(&sentence).unwrap();
+            let errors: Vec<_> = validator
+                .iter_errors(&value)
+                .map(|e| e.to_string())
+                .collect();
+            assert!(errors.is_empty(), "{}: {}\n{:?}", line, value, errors);
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! Reading and writing sentences on async streams with tokio.
+//!
+//! [`NmeaCodec`] is a `tokio-util` [`Decoder`] and [`Encoder`], turning anything that works with
+//! [`FramedRead`], [`FramedWrite`], `Framed` or `UdpFramed`, such as a `TcpStream` or a
+//! `tokio-serial` port, into a stream and a sink of sentences.  Framing works like
+//! [`crate::framer::Framer`]: line noise between sentences is skipped and a line longer than the
+//! maximum line length is dropped, so the buffer of a stream never holds much more than one line.
+//!
+//! A decoder error ends a `tokio-util` stream, so each decoded frame is itself a result and a
+//! bad sentence does not end the stream.  [`NmeaStream`] flattens them into a
+//! `Stream<Item = Result<Sentence, ParseNMEA0183Error>>`, where only I/O errors end the stream.
+//!
+//! ```
+//! # #[cfg(feature = "wind")] {
+//! use oor_nmea0183::{codec::NmeaCodec, Sentence};
+//! use tokio_util::{bytes::BytesMut, codec::Decoder};
+//!
+//! let mut codec = NmeaCodec::new();
+//! let mut buffer = BytesMut::from(&b"noise$WIMWV,049,R,000.03,N,A*03\r\n$WIMWV,049,R,000.03,N,A*FF\r\n"[..]);
+//! assert!(matches!(codec.decode(&mut buffer), Ok(Some(Ok(Sentence::Mwv(_))))));
+//! assert!(matches!(codec.decode(&mut buffer), Ok(Some(Err(_)))));
+//! assert!(matches!(codec.decode(&mut buffer), Ok(None)));
+//! assert_eq!(codec.discarded_bytes(), 5);
+//! # }
+//! ```
+//!
+//! [`FramedRead`]: tokio_util::codec::FramedRead
+//! [`FramedWrite`]: tokio_util::codec::FramedWrite
+
+use core::{
+    fmt::Write,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::{Buf, BytesMut};
+use futures_core::Stream;
+use tokio::io::AsyncRead;
+use tokio_util::codec::{Decoder, Encoder, FramedRead};
+
+use crate::{
+    config::{EncodeOptions, ParserConfig},
+    framer::{discarded, parse_frame, scan, Scan, DEFAULT_MAX_LINE_LENGTH},
+    sentences::{error::ParseNMEA0183Error, NmeaSentence},
+    NmeaParser, Sentence,
+};
+
+/// Decodes sentences from and encodes sentences to byte streams, see [`crate::codec`].
+///
+/// Sentences are encoded with [`NmeaSentence::encode`], as complete lines with a checksum.
+/// `FramedWrite` flushes its buffer once it grows past its backpressure boundary, which
+/// `FramedWrite::set_backpressure_boundary` changes.
+#[derive(Debug, Clone)]
+pub struct NmeaCodec {
+    parser: NmeaParser,
+    max_line_length: usize,
+    encode_options: EncodeOptions,
+    discarded: usize,
+}
+
+impl Default for NmeaCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NmeaCodec {
+    pub fn new() -> Self {
+        Self::with_max_line_length(DEFAULT_MAX_LINE_LENGTH)
+    }
+
+    pub fn with_max_line_length(max_line_length: usize) -> Self {
+        Self::with_parser(NmeaParser::default(), max_line_length)
+    }
+
+    /// Creates a codec that decodes sentences according to `config`.
+    pub fn with_config(config: ParserConfig) -> Self {
+        Self::with_parser(NmeaParser::with_config(config), DEFAULT_MAX_LINE_LENGTH)
+    }
+
+    pub fn with_parser(parser: NmeaParser, max_line_length: usize) -> Self {
+        NmeaCodec {
+            parser,
+            max_line_length,
+            encode_options: EncodeOptions::default(),
+            discarded: 0,
+        }
+    }
+
+    /// Sets how sentences are encoded.
+    pub fn with_encode_options(self, encode_options: EncodeOptions) -> Self {
+        NmeaCodec {
+            encode_options,
+            ..self
+        }
+    }
+
+    /// See [`crate::framer::Framer::discarded_bytes`].
+    pub fn discarded_bytes(&self) -> usize {
+        self.discarded
+    }
+}
+
+impl Decoder for NmeaCodec {
+    type Item = Result<Sentence, ParseNMEA0183Error>;
+    type Error = ParseNMEA0183Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match scan(src, self.max_line_length) {
+            Scan::Frame {
+                skipped,
+                start,
+                end,
+                consumed,
+            } => {
+                self.discarded += discarded(&src[..skipped]);
+                let frame = src.split_to(consumed);
+                Ok(Some(parse_frame(&self.parser, &frame[start..end])))
+            }
+            Scan::TooLong { skipped, consumed } => {
+                self.discarded += discarded(&src[..skipped]) + consumed - skipped;
+                src.advance(consumed);
+                Ok(Some(Err(ParseNMEA0183Error::SentenceTooLong(
+                    self.max_line_length,
+                ))))
+            }
+            Scan::Incomplete { skipped } => {
+                self.discarded += discarded(&src[..skipped]);
+                src.advance(skipped);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Decodes what is left at the end of the stream, or of a datagram, as a final,
+    /// unterminated sentence.
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if let Some(sentence) = self.decode(src)? {
+            return Ok(Some(sentence));
+        }
+        if src.is_empty() {
+            return Ok(None);
+        }
+        let frame = src.split();
+        Ok(Some(parse_frame(&self.parser, &frame)))
+    }
+}
+
+impl<T: NmeaSentence> Encoder<T> for NmeaCodec {
+    type Error = ParseNMEA0183Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        // Writing to a `BytesMut` only fails when it cannot grow, which panics instead.
+        let _ = write!(dst, "{}", item.encode(&self.encode_options)?);
+        Ok(())
+    }
+}
+
+/// Stream of the sentences read from any [`AsyncRead`] implementation, the async counterpart
+/// of [`crate::framer::NmeaReader`].
+///
+/// Each item is the result of decoding one sentence, so a bad sentence does not end the stream.
+/// An I/O error is returned once, after which the stream ends.
+#[derive(Debug)]
+pub struct NmeaStream<R> {
+    inner: FramedRead<R, NmeaCodec>,
+}
+
+impl<R: AsyncRead> NmeaStream<R> {
+    pub fn new(inner: R) -> Self {
+        Self::with_codec(inner, NmeaCodec::new())
+    }
+
+    pub fn with_codec(inner: R, codec: NmeaCodec) -> Self {
+        NmeaStream {
+            inner: FramedRead::new(inner, codec),
+        }
+    }
+
+    /// See [`crate::framer::Framer::discarded_bytes`].
+    pub fn discarded_bytes(&self) -> usize {
+        self.inner.decoder().discarded_bytes()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for NmeaStream<R> {
+    type Item = Result<Sentence, ParseNMEA0183Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner)
+            .poll_next(cx)
+            .map(|frame| frame.map(|sentence| sentence.and_then(|sentence| sentence)))
+    }
+}
+
+#[cfg(all(test, feature = "all-sentences"))]
+mod tests {
+    use super::*;
+    use std::future::poll_fn;
+
+    async fn next<R: AsyncRead + Unpin>(
+        stream: &mut NmeaStream<R>,
+    ) -> Option<Result<Sentence, ParseNMEA0183Error>> {
+        poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+    }
+
+    #[test]
+    fn test_decode_partial_lines() {
+        let mut codec = NmeaCodec::new();
+        let mut buffer = BytesMut::new();
+        for chunk in [
+            &b"\x00\xff$WIMWV,049,R,000."[..],
+            b"03,N,A*03\r",
+            b"\n$HEHDT",
+        ] {
+            buffer.extend_from_slice(chunk);
+            if let Some(sentence) = codec.decode(&mut buffer).unwrap() {
+                assert!(matches!(sentence, Ok(Sentence::Mwv(_))));
+            }
+        }
+        assert_eq!(&buffer[..], b"$HEHDT");
+        buffer.extend_from_slice(b",238.5,T");
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+        assert!(matches!(
+            codec.decode_eof(&mut buffer),
+            Ok(Some(Ok(Sentence::Hdt(_))))
+        ));
+        assert!(codec.decode_eof(&mut buffer).unwrap().is_none());
+        assert_eq!(codec.discarded_bytes(), 2);
+    }
+
+    #[test]
+    fn test_decode_bounded_buffer() {
+        let mut codec = NmeaCodec::with_max_line_length(32);
+        let mut buffer = BytesMut::from(&[b'#'; 100][..]);
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+        assert!(buffer.is_empty());
+
+        buffer.extend_from_slice(b"$PSVSS,0.0000,0.0000,0.0000,0.0000,0.0000");
+        assert!(matches!(
+            codec.decode(&mut buffer),
+            Ok(Some(Err(ParseNMEA0183Error::SentenceTooLong(32))))
+        ));
+        assert!(buffer.is_empty());
+        assert_eq!(codec.discarded_bytes(), 100 + 41);
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut codec = NmeaCodec::new();
+        let mut buffer = BytesMut::new();
+        for _ in 0..2 {
+            let mwv = NmeaParser::parse("$WIMWV,049,R,000.03,N,A").unwrap();
+            codec.encode(mwv, &mut buffer).unwrap();
+        }
+        assert_eq!(
+            &buffer[..],
+            b"$WIMWV,49,R,0.03,N,A*33\r\n$WIMWV,49,R,0.03,N,A*33\r\n"
+        );
+
+        let mut codec = codec.with_encode_options(EncodeOptions {
+            precision: Some(1),
+            crlf: false,
+        });
+        let mut buffer = BytesMut::new();
+        let hdt = NmeaParser::parse("$HEHDT,238.55,T").unwrap();
+        codec.encode(hdt, &mut buffer).unwrap();
+        assert_eq!(&buffer[..], b"$HEHDT,238.6,T*20");
+    }
+
+    #[tokio::test]
+    async fn test_stream() {
+        let input: &[u8] = b"$WIMWV,049,R,000.03,N,A*03\r\n$WIMW\xff\r\nnoise$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56\r\n$PSVSI,WINDMILL*73";
+        let mut stream = NmeaStream::new(input);
+
+        assert!(matches!(
+            next(&mut stream).await,
+            Some(Ok(Sentence::Mwv(_)))
+        ));
+        assert!(matches!(
+            next(&mut stream).await,
+            Some(Err(ParseNMEA0183Error::ParseGrammarError))
+        ));
+        assert!(matches!(
+            next(&mut stream).await,
+            Some(Ok(Sentence::Gga(_)))
+        ));
+        assert!(matches!(
+            next(&mut stream).await,
+            Some(Ok(Sentence::Svsi(_)))
+        ));
+        assert!(next(&mut stream).await.is_none());
+        assert_eq!(stream.discarded_bytes(), 5);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    storage::{List, String},
+    talker::TalkerId,
+};
+
+/// How the `*hh` checksum of a sentence, and of its TAG block, is treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChecksumPolicy {
+    /// Sentences without a checksum are rejected.
+    Required,
+    /// The checksum is verified if present, sentences without one are accepted.
+    #[default]
+    Optional,
+    /// The checksum is never verified.
+    Ignored,
+}
+
+/// What to do with sentences that have no decoder, neither in this crate nor in the parser's
+/// [`crate::registry::SentenceRegistry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownSentencePolicy {
+    /// Return them as [`crate::Sentence::Unknown`].
+    #[default]
+    PassThrough,
+    /// Reject them with [`crate::sentences::error::ParseNMEA0183Error::UnknownSentence`].
+    Error,
+}
+
+/// Settings for [`crate::NmeaParser::with_config`].
+///
+/// The default configuration accepts everything [`crate::NmeaParser::parse`] does.  Filters
+/// compare against the talker ID (e.g. `GP`, `P`) and the message ID (e.g. `GGA`, or `GILT` for
+/// the proprietary `$PGILT`) of a sentence.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use oor_nmea0183::{
+///     config::{ChecksumPolicy, ParserConfig},
+///     NmeaParser,
+/// };
+///
+/// let parser = NmeaParser::with_config(ParserConfig {
+///     checksum: ChecksumPolicy::Required,
+///     allowed_talkers: Some(vec!["WI".to_string()]),
+///     ..Default::default()
+/// });
+/// assert!(parser.parse_line("$WIMWV,049,R,000.03,N,A*03").is_ok());
+/// assert!(parser.parse_line("$WIMWV,049,R,000.03,N,A").is_err());
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParserConfig {
+    pub checksum: ChecksumPolicy,
+    /// Maximum length of a sentence from its start delimiter up to and including the checksum.
+    /// TAG blocks and line endings are not counted.
+    pub max_sentence_length: Option<usize>,
+    /// If set, only sentences from these talkers are accepted.  Talkers are matched by their
+    /// [`TalkerId::prefix`], so `P` matches every proprietary sentence; use the message filters
+    /// to select proprietary sentences by manufacturer.
+    pub allowed_talkers: Option<List<String>>,
+    pub denied_talkers: List<String>,
+    /// If set, only sentences with these message IDs are accepted.
+    pub allowed_messages: Option<List<String>>,
+    pub denied_messages: List<String>,
+    pub unknown_sentences: UnknownSentencePolicy,
+}
+
+/// NMEA0183 limits a sentence to 82 characters including the trailing `<CR><LF>`.
+pub const NMEA0183_MAX_SENTENCE_LENGTH: usize = 80;
+
+impl ParserConfig {
+    /// Configuration that follows the standard to the letter: a checksum is required, sentences
+    /// may not exceed 82 characters and sentences without a decoder are rejected.
+    pub fn strict() -> Self {
+        ParserConfig {
+            checksum: ChecksumPolicy::Required,
+            max_sentence_length: Some(NMEA0183_MAX_SENTENCE_LENGTH),
+            unknown_sentences: UnknownSentencePolicy::Error,
+            ..Default::default()
+        }
+    }
+
+    /// Configuration for unreliable instruments that never checks checksums.
+    pub fn lenient() -> Self {
+        ParserConfig {
+            checksum: ChecksumPolicy::Ignored,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn accepts_talker(&self, talker_id: &TalkerId) -> bool {
+        let matches = |id: &String| talker_id.prefix() == id;
+        accepts(&self.allowed_talkers, &self.denied_talkers, matches)
+    }
+
+    /// Message filters of proprietary sentences match the manufacturer mnemonic followed by the
+    /// message ID, e.g. `GILT`.
+    pub(crate) fn accepts_message(&self, talker_id: &TalkerId, message_id: &str) -> bool {
+        let manufacturer = talker_id.manufacturer().unwrap_or_default();
+        let matches = |id: &String| {
+            id.strip_prefix(manufacturer)
+                .is_some_and(|id| id == message_id)
+        };
+        accepts(&self.allowed_messages, &self.denied_messages, matches)
+    }
+}
+
+/// Settings for [`crate::NmeaSentence::encode`].
+///
+/// The default writes numbers in the shortest form that decodes to the same value, so that a
+/// decoded sentence is encoded without losing precision, and ends lines in `<CR><LF>`.
+///
+/// ```
+/// # #[cfg(feature = "wind")] {
+/// use oor_nmea0183::{config::EncodeOptions, NmeaParser, NmeaSentence};
+///
+/// let mwv = NmeaParser::parse("$WIMWV,049,R,000.03,N,A*03").unwrap();
+/// assert_eq!(
+///     mwv.encode(&EncodeOptions::default()).unwrap().to_string(),
+///     "$WIMWV,49,R,0.03,N,A*33\r\n"
+/// );
+/// let options = EncodeOptions {
+///     precision: Some(1),
+///     crlf: false,
+/// };
+/// assert_eq!(mwv.encode(&options).unwrap().to_string(), "$WIMWV,49,R,0.0,N,A*00");
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// Number of decimals of the fields that are not integers, for the sentences listed in the
+    /// [`crate::catalogue`].  Digits before the decimal point, such as the leading zeros of a
+    /// `ddmm.mm` latitude, are kept.  Minutes of a latitude or longitude that round up to 60
+    /// are carried into the degrees, and `hhmmss.ss` times are not rounded.
+    pub precision: Option<usize>,
+    /// Whether lines end in `<CR><LF>`.
+    pub crlf: bool,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        EncodeOptions {
+            precision: None,
+            crlf: true,
+        }
+    }
+}
+
+fn accepts(
+    allowed: &Option<List<String>>,
+    denied: &[String],
+    matches: impl Fn(&String) -> bool,
+) -> bool {
+    allowed
+        .as_ref()
+        .is_none_or(|allowed| allowed.iter().any(&matches))
+        && !denied.iter().any(&matches)
+}
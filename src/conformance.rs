@@ -0,0 +1,665 @@
+//! Checks sentences against the NMEA0183 standard, for certifying the output of an instrument.
+//!
+//! Parsing is lenient, it accepts whatever can be decoded.  [`validate`] is strict instead: it
+//! reports every violation of the selected version of the standard in a line, each at the byte
+//! offset it was found at, rather than stopping at the first.
+//!
+//! Every sentence is checked for its length, characters, address and checksum.  Sentences listed
+//! in the [`crate::catalogue`] are checked field by field as well: required fields, the format
+//! of numbers, times and positions, status letters and other enumerated values, and the talkers
+//! that send them.
+//!
+//! ```
+//! # #[cfg(feature = "gnss")] {
+//! use oor_nmea0183::conformance::{validate, FieldFormat, Profile, Violation, ViolationKind};
+//!
+//! let line = "$GPGGA,253342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000";
+//! assert_eq!(
+//!     validate(line, Profile::V4).as_slice(),
+//!     [
+//!         Violation {
+//!             position: 7,
+//!             kind: ViolationKind::InvalidField {
+//!                 index: 0,
+//!                 name: "fix_time",
+//!                 format: FieldFormat::Time,
+//!             },
+//!         },
+//!         Violation {
+//!             position: 72,
+//!             kind: ViolationKind::MissingChecksum,
+//!         },
+//!     ]
+//! );
+//! assert!(validate(line, Profile::V2).len() == 1);
+//! # }
+//! ```
+
+use crate::{
+    catalogue,
+    config::NMEA0183_MAX_SENTENCE_LENGTH,
+    field::{FieldMeta, FieldType},
+    storage::{TryPush, Vec},
+};
+use core::fmt;
+use thiserror::Error;
+
+/// The version of the standard a sentence is validated against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Profile {
+    /// NMEA0183 v2.x: checksums are optional, there are no TAG blocks nor `!` encapsulation
+    /// sentences, and positions come from GPS alone.
+    V2,
+    /// NMEA0183 v4.x: checksums are required, and GLONASS, Galileo, BeiDou, QZSS, NavIC and
+    /// combined systems send positions under talker IDs of their own.
+    #[default]
+    V4,
+}
+
+/// A violation of the standard found by [`validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Byte offset in the line of the character, field or part of the sentence violating the
+    /// standard.
+    pub position: usize,
+    pub kind: ViolationKind,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+    #[error("Sentence is {0} characters long including <CR><LF>, the limit is 82")]
+    TooLong(usize),
+    #[error("Character {0:?} is not allowed in a sentence")]
+    InvalidCharacter(char),
+    #[error("Character {0:?} is reserved")]
+    ReservedCharacter(char),
+    #[error("TAG blocks are not part of this version of the standard")]
+    TagBlockNotSupported,
+    #[error("TAG block is malformed")]
+    InvalidTagBlock,
+    #[error("Sentence does not start with '$' or '!'")]
+    MissingStartDelimiter,
+    #[error("Encapsulation sentences are not part of this version of the standard")]
+    EncapsulationNotSupported,
+    #[error("Address is neither a talker ID and sentence formatter nor a proprietary address")]
+    InvalidAddress,
+    #[error("Talker is not expected to send this sentence")]
+    UnexpectedTalker,
+    #[error("Sentence has no checksum")]
+    MissingChecksum,
+    #[error("Checksum is not two hex digits at the end of the sentence")]
+    InvalidChecksum,
+    #[error("Checksum does not match, the computed checksum is {0:02X}")]
+    ChecksumMismatch(u8),
+    #[error("Sentence has {found} fields but requires {expected}")]
+    MissingFields { expected: usize, found: usize },
+    #[error("Sentence has {found} fields but defines only {expected}")]
+    TooManyFields { expected: usize, found: usize },
+    #[error("Field {index} ({name}) must not be empty")]
+    EmptyField { index: usize, name: &'static str },
+    #[error("Field {index} ({name}) should be {format}")]
+    InvalidField {
+        index: usize,
+        name: &'static str,
+        format: FieldFormat,
+    },
+}
+
+/// The format a field is validated against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldFormat {
+    Integer,
+    /// A decimal number without exponent.
+    Number,
+    /// UTC time as `hhmmss.ss`, with any number of decimals.
+    Time,
+    /// Latitude as degrees and minutes, `llll.ll`.
+    Latitude,
+    /// Longitude as degrees and minutes, `yyyyy.yy`.
+    Longitude,
+    /// One of the listed values, e.g. the status letters `A` and `V`.
+    OneOf(&'static [&'static str]),
+}
+
+impl fmt::Display for FieldFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldFormat::Integer => f.write_str("an integer"),
+            FieldFormat::Number => f.write_str("a number"),
+            FieldFormat::Time => f.write_str("hhmmss.ss"),
+            FieldFormat::Latitude => f.write_str("llll.ll"),
+            FieldFormat::Longitude => f.write_str("yyyyy.yy"),
+            FieldFormat::OneOf(values) => {
+                f.write_str("one of ")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    f.write_str(value)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl FieldFormat {
+    fn of(field: &FieldMeta) -> Option<Self> {
+        match field.ty {
+            FieldType::Integer => Some(FieldFormat::Integer),
+            FieldType::Float => Some(FieldFormat::Number),
+            FieldType::Text => None,
+            FieldType::Enum(values) => Some(FieldFormat::OneOf(values)),
+        }
+    }
+
+    fn matches(self, field: &str) -> bool {
+        if !field.is_ascii() {
+            return false;
+        }
+        let unsigned = field.strip_prefix(['+', '-']).unwrap_or(field);
+        match self {
+            FieldFormat::Integer => digits(unsigned).is_some(),
+            FieldFormat::Number => {
+                let (whole, fraction) = split_decimal(unsigned);
+                digits(whole).is_some() && fraction.is_none_or(|f| digits(f).is_some())
+            }
+            FieldFormat::Time => {
+                let (whole, fraction) = split_decimal(field);
+                whole.len() == 6
+                    && fraction.is_none_or(|f| digits(f).is_some())
+                    && matches!(
+                        (
+                            digits(&whole[..2]),
+                            digits(&whole[2..4]),
+                            digits(&whole[4..])
+                        ),
+                        (Some(0..=23), Some(0..=59), Some(0..=59))
+                    )
+            }
+            FieldFormat::Latitude => is_angle(field, 2, 90),
+            FieldFormat::Longitude => is_angle(field, 3, 180),
+            FieldFormat::OneOf(values) => values.contains(&field),
+        }
+    }
+}
+
+/// Fields of approved sentences with a format stricter than their type, by message ID and index.
+const FORMATS: &[(&str, usize, FieldFormat)] = &[
+    ("GGA", 0, FieldFormat::Time),
+    ("GGA", 1, FieldFormat::Latitude),
+    ("GGA", 3, FieldFormat::Longitude),
+];
+
+/// The talkers expected to send an approved sentence, any talker may send the ones not listed.
+fn talkers(message_id: &str, profile: Profile) -> Option<&'static [&'static str]> {
+    Some(match (message_id, profile) {
+        ("GGA", Profile::V2) => &["GP", "IN"],
+        ("GGA", Profile::V4) => &["GA", "GB", "GI", "GL", "GN", "GP", "GQ", "IN"],
+        ("HDM", _) => &["HC", "II", "IN"],
+        ("HDT", Profile::V2) => &["GP", "HE", "II", "IN"],
+        ("HDT", Profile::V4) => &["GN", "GP", "HE", "II", "IN"],
+        ("MWV", _) => &["II", "WI"],
+        _ => return None,
+    })
+}
+
+/// The value of a non-empty string of ASCII digits.
+fn digits(s: &str) -> Option<u32> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(s.parse().unwrap_or(u32::MAX))
+}
+
+fn split_decimal(field: &str) -> (&str, Option<&str>) {
+    match field.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (field, None),
+    }
+}
+
+/// Whether `field` is an angle of at most `max` degrees written with `degrees` digits, followed
+/// by the minutes.
+fn is_angle(field: &str, degrees: usize, max: u32) -> bool {
+    let (whole, fraction) = split_decimal(field);
+    whole.len() == degrees + 2
+        && fraction.is_none_or(|f| digits(f).is_some())
+        && matches!(
+            (digits(&whole[..degrees]), digits(&whole[degrees..])),
+            (Some(d), Some(0..=59)) if d <= max
+        )
+}
+
+fn is_valid_address(address: &str) -> bool {
+    let letters = |s: &str| s.bytes().all(|b| b.is_ascii_uppercase());
+    if !address
+        .bytes()
+        .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+    {
+        return false;
+    }
+    match address.strip_prefix('P') {
+        Some(proprietary) if address.len() >= 4 => letters(&proprietary[..3]),
+        _ => address.len() == 5 && letters(&address[2..]),
+    }
+}
+
+/// The value of a checksum written as two hex digits.
+fn hex(checksum: &str) -> Option<u8> {
+    let valid = checksum.len() == 2 && checksum.bytes().all(|b| b.is_ascii_hexdigit());
+    valid.then(|| u8::from_str_radix(checksum, 16).unwrap_or_default())
+}
+
+/// Collects the violations found in a line, ordered by position and, at the same position, in
+/// the order they were found.
+struct Violations(Vec<Violation>);
+
+impl Violations {
+    fn push(&mut self, position: usize, kind: ViolationKind) {
+        // Without `alloc` the violations beyond the capacity of the vector are dropped.
+        if self.0.try_push(Violation { position, kind }).is_ok() {
+            let at = self.0[..self.0.len() - 1].partition_point(|v| v.position <= position);
+            self.0[at..].rotate_right(1);
+        }
+    }
+}
+
+/// Validates `line` against version `profile` of the standard, returning every violation found,
+/// ordered by position.
+///
+/// The line may end in `<CR><LF>`, which counts towards the 82 character limit either way.  A
+/// TAG block does not count towards it.  Without the `alloc` feature only as many violations as
+/// fit in a [`crate::storage::Vec`] are returned.
+pub fn validate(line: &str, profile: Profile) -> Vec<Violation> {
+    let mut violations = Violations(Vec::new());
+    let line = line.strip_suffix("\r\n").unwrap_or(line);
+
+    let body_start = if let Some(tagged) = line.strip_prefix('\\') {
+        let Some(end) = tagged.find('\\').map(|end| end + 1) else {
+            violations.push(0, ViolationKind::InvalidTagBlock);
+            return violations.0;
+        };
+        if profile == Profile::V2 {
+            violations.push(0, ViolationKind::TagBlockNotSupported);
+        }
+        match line[1..end].rsplit_once('*') {
+            Some((params, checksum)) => match hex(checksum) {
+                Some(expected) => {
+                    let computed = crate::checksum(params);
+                    if computed != expected {
+                        violations
+                            .push(params.len() + 1, ViolationKind::ChecksumMismatch(computed));
+                    }
+                }
+                _ => violations.push(params.len() + 1, ViolationKind::InvalidChecksum),
+            },
+            None => violations.push(0, ViolationKind::InvalidTagBlock),
+        }
+        end + 1
+    } else {
+        0
+    };
+
+    let data_start = match line[body_start..].chars().next() {
+        Some('$') => body_start + 1,
+        Some('!') => {
+            if profile == Profile::V2 {
+                violations.push(body_start, ViolationKind::EncapsulationNotSupported);
+            }
+            body_start + 1
+        }
+        _ => {
+            violations.push(body_start, ViolationKind::MissingStartDelimiter);
+            body_start
+        }
+    };
+
+    let (data_end, checksum) = match line[data_start..].rfind('*') {
+        Some(star) => (data_start + star, Some(&line[data_start + star + 1..])),
+        None => (line.len(), None),
+    };
+    let data = &line[data_start..data_end];
+
+    let length = line.len() - body_start + 2;
+    if length > NMEA0183_MAX_SENTENCE_LENGTH + 2 {
+        violations.push(
+            body_start + NMEA0183_MAX_SENTENCE_LENGTH,
+            ViolationKind::TooLong(length),
+        );
+    }
+
+    let mut chars = data.char_indices();
+    while let Some((i, c)) = chars.next() {
+        let kind = match c {
+            ',' => continue,
+            '^' if data
+                .get(i + 1..i + 3)
+                .is_some_and(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit())) =>
+            {
+                chars.nth(1);
+                continue;
+            }
+            '!' | '$' | '*' | '\\' | '^' | '~' => ViolationKind::ReservedCharacter(c),
+            ' '..='~' => continue,
+            _ => ViolationKind::InvalidCharacter(c),
+        };
+        violations.push(data_start + i, kind);
+    }
+
+    match checksum {
+        Some(checksum) => match hex(checksum) {
+            Some(expected) => {
+                let computed = crate::checksum(data);
+                if computed != expected {
+                    violations.push(data_end, ViolationKind::ChecksumMismatch(computed));
+                }
+            }
+            _ => violations.push(data_end, ViolationKind::InvalidChecksum),
+        },
+        None if profile == Profile::V4 => violations.push(data_end, ViolationKind::MissingChecksum),
+        None => {}
+    }
+
+    let (address, fields) = data.split_once(',').unwrap_or((data, ""));
+    if !is_valid_address(address) {
+        violations.push(data_start, ViolationKind::InvalidAddress);
+    } else if line[body_start..].starts_with('$') {
+        let fields_start = (data_start + address.len() + 1).min(data_end);
+        validate_fields(&mut violations, address, fields, fields_start, profile);
+    }
+
+    violations.0
+}
+
+/// Validates the fields of a sentence from the catalogue, starting at `position` in the line.
+fn validate_fields(
+    violations: &mut Violations,
+    address: &str,
+    fields: &str,
+    position: usize,
+    profile: Profile,
+) {
+    let (manufacturer, message_id) = match address.strip_prefix('P') {
+        Some(proprietary) => (Some(&proprietary[..3]), &proprietary[3..]),
+        None => (None, &address[2..]),
+    };
+    let Some(info) = catalogue::find(manufacturer, message_id) else {
+        return;
+    };
+
+    if manufacturer.is_none() {
+        if let Some(talkers) = talkers(message_id, profile) {
+            if !talkers.contains(&&address[..2]) {
+                violations.push(
+                    position - address.len() - 1,
+                    ViolationKind::UnexpectedTalker,
+                );
+            }
+        }
+    }
+
+    let count = fields.split(',').count();
+    let required = info
+        .fields
+        .iter()
+        .filter(|field| field.required && !field.repeated)
+        .map(|field| field.index + 1)
+        .max()
+        .unwrap_or(0);
+    if count < required {
+        violations.push(
+            position + fields.len(),
+            ViolationKind::MissingFields {
+                expected: required,
+                found: count,
+            },
+        );
+    }
+
+    let mut offset = position;
+    for (index, field) in fields.split(',').enumerate() {
+        match info.field(index) {
+            Some(meta) => validate_field(violations, info, meta, index, field, offset),
+            None if info.fields.iter().all(|meta| meta.index < index) => {
+                violations.push(
+                    offset,
+                    ViolationKind::TooManyFields {
+                        expected: index,
+                        found: count,
+                    },
+                );
+                return;
+            }
+            None => {}
+        }
+        offset += field.len() + 1;
+    }
+}
+
+fn validate_field(
+    violations: &mut Violations,
+    info: &catalogue::SentenceInfo,
+    meta: &'static FieldMeta,
+    index: usize,
+    field: &str,
+    position: usize,
+) {
+    if field.is_empty() {
+        if !meta.nullable {
+            violations.push(
+                position,
+                ViolationKind::EmptyField {
+                    index,
+                    name: meta.name,
+                },
+            );
+        }
+        return;
+    }
+    let format = FORMATS
+        .iter()
+        .find(|(message_id, i, _)| {
+            info.manufacturer.is_none() && *message_id == info.message_id && *i == meta.index
+        })
+        .map(|(_, _, format)| *format)
+        .or_else(|| FieldFormat::of(meta));
+    if let Some(format) = format.filter(|format| !format.matches(field)) {
+        violations.push(
+            position,
+            ViolationKind::InvalidField {
+                index,
+                name: meta.name,
+                format,
+            },
+        );
+    }
+}
+
+#[cfg(all(test, feature = "std", feature = "all-sentences"))]
+mod tests {
+    use super::*;
+    use crate::storage::ToString;
+
+    const GGA: &str = "$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56";
+
+    fn kinds(line: &str, profile: Profile) -> Vec<(usize, ViolationKind)> {
+        validate(line, profile)
+            .into_iter()
+            .map(|violation| (violation.position, violation.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_sentences() {
+        for line in [
+            GGA,
+            "$WIMWV,049,R,000.03,N,A*03\r\n",
+            "$WIXDR,C,+023.1,C,TEMP,P,0.9989,B,PRESS,H,040,P,RH*25",
+            "$PGILT,A,+00,D,+01,D,+1,TILT*35",
+            "$GPTXT,01,01,02,ALARM^21 ^5EHIGH^5E TEMP*41",
+        ] {
+            assert_eq!(kinds(line, Profile::V4), [], "{:?}", line);
+            assert_eq!(kinds(line, Profile::V2), [], "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_sentence_violations() {
+        let long = format!("$GPTXT,{}*22", "A".repeat(73));
+        assert_eq!(
+            kinds(&long, Profile::V4),
+            [(80, ViolationKind::TooLong(85))]
+        );
+
+        assert_eq!(
+            kinds("GPtxt,a~b\tc*3", Profile::V2),
+            [
+                (0, ViolationKind::MissingStartDelimiter),
+                (0, ViolationKind::InvalidAddress),
+                (7, ViolationKind::ReservedCharacter('~')),
+                (9, ViolationKind::InvalidCharacter('\t')),
+                (11, ViolationKind::InvalidChecksum),
+            ]
+        );
+
+        assert_eq!(
+            kinds(
+                "\\s:GP01*00\\!AIVDM,1,1,,A,13aEOK?P00PD2wVMdLDRhgvL289?,0",
+                Profile::V2
+            ),
+            [
+                (0, ViolationKind::TagBlockNotSupported),
+                (7, ViolationKind::ChecksumMismatch(0x5F)),
+                (11, ViolationKind::EncapsulationNotSupported),
+            ]
+        );
+        assert_eq!(
+            kinds(
+                "\\s:GP01*5F\\!AIVDM,1,1,,A,13aEOK?P00PD2wVMdLDRhgvL289?,0",
+                Profile::V4
+            ),
+            [(55, ViolationKind::MissingChecksum)]
+        );
+        assert_eq!(
+            kinds("\\s:GP01*2D$GPGGA,", Profile::V4),
+            [(0, ViolationKind::InvalidTagBlock)]
+        );
+    }
+
+    #[test]
+    fn test_field_violations() {
+        assert_eq!(
+            kinds(
+                "$GLGGA,113360,9145.7837,X,00132.4127,W,,06,1.3,-10.2,M,47.8,M,,0000",
+                Profile::V2
+            ),
+            [
+                (1, ViolationKind::UnexpectedTalker),
+                (
+                    7,
+                    ViolationKind::InvalidField {
+                        index: 0,
+                        name: "fix_time",
+                        format: FieldFormat::Time,
+                    }
+                ),
+                (
+                    14,
+                    ViolationKind::InvalidField {
+                        index: 1,
+                        name: "latitude",
+                        format: FieldFormat::Latitude,
+                    }
+                ),
+                (
+                    24,
+                    ViolationKind::InvalidField {
+                        index: 2,
+                        name: "latitude_hemisphere",
+                        format: FieldFormat::OneOf(&["N", "S"]),
+                    }
+                ),
+                (
+                    39,
+                    ViolationKind::EmptyField {
+                        index: 5,
+                        name: "fix_quality",
+                    }
+                ),
+            ]
+        );
+
+        assert_eq!(
+            kinds("$WIMWV,049.5,R,000.03*17", Profile::V4),
+            [
+                (
+                    7,
+                    ViolationKind::InvalidField {
+                        index: 0,
+                        name: "wind_direction_deg",
+                        format: FieldFormat::Integer,
+                    }
+                ),
+                (
+                    21,
+                    ViolationKind::MissingFields {
+                        expected: 5,
+                        found: 3,
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            kinds("$WIMWV,049,R,000.03,N,A,1,2*1F", Profile::V4)[0],
+            (
+                24,
+                ViolationKind::TooManyFields {
+                    expected: 5,
+                    found: 7,
+                }
+            )
+        );
+
+        assert_eq!(
+            kinds("$YXXDR,C,+023.1,X,TEMP,P,1e3", Profile::V2),
+            [
+                (
+                    16,
+                    ViolationKind::InvalidField {
+                        index: 2,
+                        name: "readings.units",
+                        format: FieldFormat::OneOf(&[
+                            "A", "B", "C", "D", "H", "I", "K", "M", "N", "P", "S", "V"
+                        ]),
+                    }
+                ),
+                (
+                    25,
+                    ViolationKind::InvalidField {
+                        index: 5,
+                        name: "readings.reading",
+                        format: FieldFormat::Number,
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_violation_messages() {
+        let violation = ViolationKind::InvalidField {
+            index: 4,
+            name: "acceptable",
+            format: FieldFormat::OneOf(&["A", "V"]),
+        };
+        assert_eq!(
+            violation.to_string(),
+            "Field 4 (acceptable) should be one of A, V"
+        );
+        assert_eq!(
+            ViolationKind::ChecksumMismatch(0x0E).to_string(),
+            "Checksum does not match, the computed checksum is 0E"
+        );
+    }
+}
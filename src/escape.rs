@@ -0,0 +1,114 @@
+use crate::{
+    sentences::error::ParseNMEA0183Error,
+    storage::{Bytes, Cow, String, TryPush, STRING_CAPACITY},
+};
+use core::fmt::{self, Write};
+
+/// NMEA0183 reserves `<CR>`, `<LF>`, `$`, `*`, `,`, `!`, `\`, `^`, `~` and `<DEL>`.  Inside a
+/// field they, like any other byte outside printable ASCII, are written as a `^HH` escape with
+/// the hex code of the byte.
+fn is_reserved(b: u8) -> bool {
+    matches!(b, b'$' | b'*' | b',' | b'!' | b'\\' | b'^' | b'~') || !(b' '..=b'~').contains(&b)
+}
+
+/// Decodes the escapes in a field.  Fields without escapes are borrowed as they are.
+///
+/// The escaped bytes are read as UTF-8, falling back to Latin-1 for instruments that send
+/// e.g. `^B0` for a degree sign.  [`write_escaped`] only writes UTF-8, so a field read with the
+/// fallback does not escape back to the bytes it was read from.  Without the `alloc` feature a field that does not fit into a
+/// [`String`] fails with [`ParseNMEA0183Error::CapacityExceeded`].
+pub(crate) fn unescape(field: &str) -> Result<Cow<'_, str>, ParseNMEA0183Error> {
+    if !field.contains('^') {
+        return Ok(Cow::Borrowed(field));
+    }
+    let mut bytes = Bytes::new();
+    let mut rest = field.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        rest = tail;
+        if b == b'^' {
+            // The grammar only accepts `^` followed by two hex digits.
+            if let Some(byte) = tail
+                .get(..2)
+                .and_then(|hex| core::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                bytes.try_push(byte)?;
+                rest = &tail[2..];
+                continue;
+            }
+        }
+        bytes.try_push(b)?;
+    }
+    let mut unescaped = String::new();
+    match core::str::from_utf8(&bytes) {
+        Ok(text) => unescaped.write_str(text),
+        Err(_) => bytes
+            .iter()
+            .try_for_each(|&b| unescaped.write_char(char::from(b))),
+    }
+    .map_err(|_| ParseNMEA0183Error::CapacityExceeded(STRING_CAPACITY))?;
+    Ok(Cow::Owned(unescaped))
+}
+
+/// Splits the spaces off the start of the `first` field of a sentence and off the end of the
+/// `last` one.  The grammar skips them as spaces between tokens, so they are written as escapes.
+pub(crate) fn split_spaces(field: &str, first: bool, last: bool) -> (usize, &str, usize) {
+    let rest = if first {
+        field.trim_start_matches(' ')
+    } else {
+        field
+    };
+    let middle = if last {
+        rest.trim_end_matches(' ')
+    } else {
+        rest
+    };
+    (field.len() - rest.len(), middle, rest.len() - middle.len())
+}
+
+/// Writes a field value, escaping the reserved and non printable characters.
+pub(crate) fn write_escaped(w: &mut impl Write, field: &str) -> fmt::Result {
+    let mut rest = field;
+    while let Some(i) = rest.bytes().position(is_reserved) {
+        w.write_str(&rest[..i])?;
+        // Reserved bytes are ASCII or part of a multi-byte character, which is escaped whole.
+        let len = rest[i..].chars().next().map_or(1, char::len_utf8);
+        for b in &rest.as_bytes()[i..i + len] {
+            write!(w, "^{:02X}", b)?;
+        }
+        rest = &rest[i + len..];
+    }
+    w.write_str(rest)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn escape(field: &str) -> String {
+        let mut escaped = String::new();
+        write_escaped(&mut escaped, field).unwrap();
+        escaped
+    }
+
+    #[test]
+    fn test_escape_round_trip() {
+        assert!(matches!(
+            unescape("WINDMILL").unwrap(),
+            Cow::Borrowed("WINDMILL")
+        ));
+        assert_eq!(escape("WINDMILL"), "WINDMILL");
+
+        assert_eq!(unescape("A^2CB^2a^5E").unwrap(), "A,B*^");
+        assert_eq!(unescape("25^C2^B0C").unwrap(), "25°C");
+        // The Latin-1 fallback is one way, the degree sign is escaped as UTF-8 again.
+        assert_eq!(unescape("25^B0C").unwrap(), "25°C");
+        assert_eq!(escape(&unescape("25^B0C").unwrap()), "25^C2^B0C");
+
+        for field in ["a,b*c", "$!\\^~", "line\r\n", "25°C", ""] {
+            assert_eq!(unescape(&escape(field)).unwrap(), field);
+        }
+        assert_eq!(escape("a,b*c"), "a^2Cb^2Ac");
+        assert_eq!(escape("25°C"), "25^C2^B0C");
+    }
+}
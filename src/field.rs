@@ -0,0 +1,421 @@
+//! Decoding and encoding single fields, and the derive macros built on it.
+//!
+//! `#[derive(NmeaSentence)]` implements a `decode` function taking a
+//! [`DecodeContext`](crate::sentences::DecodeContext), [`TryFrom<Nmea>`](crate::Nmea),
+//! [`NmeaSentence`] and [`SentenceMeta`] for a struct with `talker_id`, `message_id` and
+//! `tag_block` members.  Every other member is decoded from a field of the sentence:
+//!
+//! - `#[nmea(index = 3)]` sets the zero based index of the field, by default a member takes the
+//!   field after the one before it, starting at 0.
+//! - `#[nmea(unit = uom::si::length::meter)]` decodes the field as a `uom` quantity in that unit,
+//!   `#[nmea(unit = "degree")]` only names the unit of a plain number in [`FieldMeta`].
+//! - `#[nmea(optional)]` lets the sentence end before the field.  Without it a sentence with
+//!   fewer fields is rejected with [`ParseNMEA0183Error::MissingFields`].
+//! - A member of type `Option<T>` is `None` when the field is empty or missing.  An invalid
+//!   value is a [`FieldError`] that rejects the sentence, unless it is decoded leniently, then
+//!   the member is `None` and the error is kept as a warning.  Any other member makes the
+//!   sentence fail to decode with a [`FieldError`] when its field is empty, missing or invalid.
+//! - `#[nmea(non_empty)]` on an `Option<T>` member treats an empty field as invalid, so that
+//!   the member is only `None` when the value was dropped by lenient decoding.
+//! - `#[nmea(repeated)]` on the last member, a `Vec<T>`, decodes its field and every field after
+//!   it.  With `optional` as well the field can repeat zero times.
+//!
+//! The struct itself takes `#[nmea(message_id = "...")]` and, for proprietary sentences,
+//! `#[nmea(manufacturer = "...")]`.  The `message_id` member is built from that message ID with
+//! `From<&'static str>`, a `Cow<'static, str>` keeps it without copying.  Field types implement [`NmeaField`], enums do so with
+//! `#[derive(NmeaEnum)]` and a `#[nmea(value = "...")]` on each variant.
+//!
+//! ```
+//! use oor_nmea0183::{
+//!     storage::Cow, tag_block::TagBlock, talker::TalkerId, NmeaEnum, NmeaParser,
+//!     NmeaSentence, Sentence,
+//! };
+//!
+//! #[derive(Debug, NmeaEnum)]
+//! enum Status {
+//!     #[nmea(value = "A")]
+//!     Valid,
+//!     #[nmea(value = "V")]
+//!     Invalid,
+//! }
+//!
+//! #[derive(Debug, NmeaSentence)]
+//! #[nmea(manufacturer = "XYZ", message_id = "D")]
+//! struct Depth {
+//!     talker_id: TalkerId,
+//!     message_id: Cow<'static, str>,
+//!     tag_block: Option<TagBlock>,
+//!     depth: f32,
+//!     #[nmea(index = 2)]
+//!     status: Option<Status>,
+//! }
+//!
+//! let Sentence::Unknown { nmea, .. } = NmeaParser::parse("$PXYZD,12.5,M,A").unwrap() else {
+//!     panic!("Expected an unknown sentence");
+//! };
+//! let depth = Depth::try_from(nmea).unwrap();
+//! assert_eq!(depth.depth, 12.5);
+//! assert!(matches!(depth.status, Some(Status::Valid)));
+//! assert_eq!(depth.to_nmea().unwrap().to_string(), "$PXYZD,12.5,,A*3A");
+//! ```
+//!
+//! [`FieldError`]: crate::sentences::error::FieldError
+
+use crate::{
+    sentences::{
+        error::{FieldErrorKind, ParseNMEA0183Error},
+        NmeaSentence,
+    },
+    storage::{Cow, String, ToString, TryPush, Vec},
+    Delimiter, Nmea,
+};
+
+/// The kind of value a field holds, for describing sentences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Integer,
+    Float,
+    Text,
+    /// One of the listed values.
+    Enum(&'static [&'static str]),
+}
+
+/// A value that is read from and written to a single field.
+pub trait NmeaField: Sized {
+    const TYPE: FieldType;
+
+    /// Decodes a field that is not empty.
+    fn decode_field(field: &str) -> Result<Self, FieldErrorKind>;
+
+    fn encode_field(&self) -> Cow<'static, str>;
+}
+
+macro_rules! number_field {
+    ($ty:ty, $kind:ident) => {
+        impl NmeaField for $ty {
+            const TYPE: FieldType = FieldType::$kind;
+
+            fn decode_field(field: &str) -> Result<Self, FieldErrorKind> {
+                Ok(field.parse()?)
+            }
+
+            fn encode_field(&self) -> Cow<'static, str> {
+                Cow::Owned(self.to_string())
+            }
+        }
+    };
+}
+
+number_field!(f32, Float);
+number_field!(f64, Float);
+number_field!(i8, Integer);
+number_field!(i16, Integer);
+number_field!(i32, Integer);
+number_field!(i64, Integer);
+number_field!(u8, Integer);
+number_field!(u16, Integer);
+number_field!(u32, Integer);
+number_field!(u64, Integer);
+
+impl NmeaField for String {
+    const TYPE: FieldType = FieldType::Text;
+
+    fn decode_field(field: &str) -> Result<Self, FieldErrorKind> {
+        Ok(field.to_string())
+    }
+
+    fn encode_field(&self) -> Cow<'static, str> {
+        Cow::Owned(self.clone())
+    }
+}
+
+/// The numeric type a `uom` quantity stores its value in, used for fields with a `unit`.
+#[cfg(feature = "uom")]
+pub trait Quantity {
+    type Value: NmeaField;
+}
+
+#[cfg(feature = "uom")]
+impl<D, U, V> Quantity for uom::si::Quantity<D, U, V>
+where
+    D: uom::si::Dimension + ?Sized,
+    U: uom::si::Units<V> + ?Sized,
+    V: uom::num::Num + uom::Conversion<V> + NmeaField,
+{
+    type Value = V;
+}
+
+/// Describes a field of a sentence, see [`SentenceMeta`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldMeta {
+    /// Name of the struct member the field is decoded into, `member.name` for a field decoded
+    /// into a member of a nested struct.  Fields that are not decoded are named after what they
+    /// hold, e.g. `latitude_hemisphere`.
+    pub name: &'static str,
+    /// Zero based index of the field, not counting the talker and message ID.
+    pub index: usize,
+    pub ty: FieldType,
+    /// The unit of a quantity, e.g. `meter`, or `hhmmss`, `ddmm` and `dddmm` for a time of day
+    /// and the degrees and minutes of a latitude or longitude.
+    pub unit: Option<&'static str>,
+    /// Whether the field can be empty.
+    pub nullable: bool,
+    /// Whether the member the field is decoded into is an `Option`, which is `None` when the
+    /// field is empty or invalid even if the field cannot be empty.
+    pub option: bool,
+    /// Whether the sentence has to include the field.
+    pub required: bool,
+    /// Whether the field repeats until the end of the sentence, together with the repeated
+    /// fields after it.
+    pub repeated: bool,
+}
+
+impl FieldMeta {
+    /// A required field that cannot be empty.
+    pub const fn new(name: &'static str, index: usize, ty: FieldType) -> Self {
+        FieldMeta {
+            name,
+            index,
+            ty,
+            unit: None,
+            nullable: false,
+            option: false,
+            required: true,
+            repeated: false,
+        }
+    }
+
+    pub const fn unit(self, unit: &'static str) -> Self {
+        FieldMeta {
+            unit: Some(unit),
+            ..self
+        }
+    }
+
+    pub const fn nullable(self) -> Self {
+        FieldMeta {
+            nullable: true,
+            option: true,
+            ..self
+        }
+    }
+
+    /// Marks a field that cannot be empty, but is decoded into an `Option` anyway.
+    pub const fn option(self) -> Self {
+        FieldMeta {
+            option: true,
+            ..self
+        }
+    }
+
+    pub const fn repeated(self) -> Self {
+        FieldMeta {
+            repeated: true,
+            ..self
+        }
+    }
+}
+
+/// The message ID and fields of a sentence struct, implemented by `#[derive(NmeaSentence)]`
+/// and listed by [`crate::catalogue`].
+pub trait SentenceMeta {
+    /// The manufacturer mnemonic of a proprietary sentence.
+    const MANUFACTURER: Option<&'static str>;
+    const MESSAGE_ID: &'static str;
+    const FIELDS: &'static [FieldMeta];
+}
+
+/// Decodes field `index`, `None` if it is empty or missing, failing with a field error naming it
+/// as `name` if it is invalid.
+pub fn optional<T: NmeaField>(
+    nmea: &Nmea<'_>,
+    index: usize,
+    name: &'static str,
+) -> Result<Option<T>, ParseNMEA0183Error> {
+    match nmea.fields.get(index).filter(|field| !field.is_empty()) {
+        Some(field) => T::decode_field(field)
+            .map(Some)
+            .map_err(|kind| nmea.field_error(index, name, kind)),
+        None => Ok(None),
+    }
+}
+
+/// Decodes field `index`, failing with a field error naming it as `name` if it is empty,
+/// missing or invalid.
+pub fn required<T: NmeaField>(
+    nmea: &Nmea<'_>,
+    index: usize,
+    name: &'static str,
+) -> Result<T, ParseNMEA0183Error> {
+    let field = nmea.fields.get(index).map_or("", |field| field);
+    T::decode_field(field).map_err(|kind| nmea.field_error(index, name, kind))
+}
+
+/// Builds the [`Nmea`] of a decoded sentence from its header and encoded fields.
+///
+/// Without the `alloc` feature this fails with [`ParseNMEA0183Error::CapacityExceeded`] when
+/// there are more than [`crate::storage::VEC_CAPACITY`] fields.
+pub fn encode(
+    sentence: &impl NmeaSentence,
+    fields: impl IntoIterator<Item = Cow<'static, str>>,
+) -> Result<Nmea<'static>, ParseNMEA0183Error> {
+    let mut encoded = Vec::new();
+    for field in fields {
+        encoded.try_push(field)?;
+    }
+    Ok(Nmea {
+        tag_block: sentence.tag_block().cloned(),
+        delimiter: Delimiter::Parametric,
+        talker_id: sentence.talker_id().clone(),
+        message_id: Cow::Owned(sentence.message_id().into_owned()),
+        fields: encoded,
+    })
+}
+
+#[cfg(all(test, feature = "std", feature = "all-sentences"))]
+mod tests {
+    use super::*;
+    use crate::{
+        sentences::{error::FieldError, seaview::psvdy::Svdy, DecodeContext, DecodeMode},
+        tag_block::TagBlock,
+        talker::TalkerId,
+        NmeaEnum, NmeaParser, NmeaSentence,
+    };
+    use uom::si::{f32::Length, length::meter};
+
+    #[derive(Debug, Clone, Copy, PartialEq, NmeaEnum)]
+    enum Status {
+        #[nmea(value = "A")]
+        Valid,
+        #[nmea(value = "V")]
+        Invalid,
+    }
+
+    #[derive(Debug, NmeaSentence)]
+    #[nmea(message_id = "DPT")]
+    struct Dpt {
+        talker_id: TalkerId,
+        message_id: Cow<'static, str>,
+        tag_block: Option<TagBlock>,
+        #[nmea(unit = meter)]
+        depth: Length,
+        #[nmea(index = 2)]
+        status: Option<Status>,
+        #[nmea(optional)]
+        name: Option<String>,
+    }
+
+    fn dpt(line: &str) -> Result<Dpt, ParseNMEA0183Error> {
+        Dpt::try_from(NmeaParser::to_nmea(line).unwrap())
+    }
+
+    #[test]
+    fn test_derived_decoder() {
+        let decoded = dpt("$SDDPT,12.5,,V,KEEL").unwrap();
+        assert_eq!(decoded.depth.get::<meter>(), 12.5);
+        assert_eq!(decoded.status, Some(Status::Invalid));
+        assert_eq!(decoded.name.as_deref(), Some("KEEL"));
+
+        let nmea = NmeaParser::to_nmea("$SDDPT,12.5,,X").unwrap();
+        match Dpt::try_from(nmea.clone()) {
+            Err(ParseNMEA0183Error::InvalidField(e)) => {
+                assert_eq!((e.index, e.name, e.raw.as_str()), (2, Some("status"), "X"));
+            }
+            other => panic!("Expected a field error, got {:?}", other),
+        }
+        let mut ctx = DecodeContext::new(DecodeMode::Lenient);
+        let decoded = Dpt::decode(nmea, &mut ctx).unwrap();
+        assert_eq!(decoded.status, None);
+        assert_eq!(decoded.name, None);
+        assert_eq!(ctx.warnings().len(), 1);
+        assert_eq!(ctx.warnings()[0].name, Some("status"));
+
+        assert!(matches!(
+            dpt("$SDDPT,12.5,"),
+            Err(ParseNMEA0183Error::MissingFields(3))
+        ));
+        match dpt("$SDDPT,,,A") {
+            Err(ParseNMEA0183Error::InvalidField(e)) => {
+                let FieldError { index, name, .. } = *e;
+                assert_eq!((index, name), (0, Some("depth")));
+            }
+            other => panic!("Expected a field error, got {:?}", other),
+        }
+    }
+
+    #[derive(Debug, NmeaSentence)]
+    #[nmea(manufacturer = "XYZ", message_id = "B")]
+    struct Bins {
+        talker_id: TalkerId,
+        message_id: Cow<'static, str>,
+        tag_block: Option<TagBlock>,
+        #[nmea(non_empty)]
+        status: Option<Status>,
+        #[nmea(repeated)]
+        depths: Vec<f32>,
+    }
+
+    #[test]
+    fn test_derived_repeated_and_non_empty() {
+        let bins = |line| Bins::try_from(NmeaParser::to_nmea(line).unwrap());
+        let decoded = bins("$PXYZB,A,1.5,2,3.25").unwrap();
+        assert_eq!(decoded.status, Some(Status::Valid));
+        assert_eq!(decoded.depths, [1.5, 2.0, 3.25]);
+        assert_eq!(
+            decoded.to_nmea().unwrap().to_string(),
+            "$PXYZB,A,1.5,2,3.25*0A"
+        );
+        assert!(matches!(
+            bins("$PXYZB,A"),
+            Err(ParseNMEA0183Error::MissingFields(2))
+        ));
+        assert!(matches!(
+            bins("$PXYZB,A,1.5,x"),
+            Err(ParseNMEA0183Error::InvalidField(e)) if e.index == 2
+        ));
+
+        let nmea = NmeaParser::to_nmea("$PXYZB,,1.5").unwrap();
+        assert!(Bins::try_from(nmea.clone()).is_err());
+        let mut ctx = DecodeContext::new(DecodeMode::Lenient);
+        assert_eq!(Bins::decode(nmea, &mut ctx).unwrap().status, None);
+        assert_eq!(ctx.warnings()[0].name, Some("status"));
+
+        assert!(!Bins::FIELDS[0].nullable);
+        assert!(Bins::FIELDS[0].option);
+        assert!(Bins::FIELDS[1].repeated);
+    }
+
+    #[test]
+    fn test_derived_encoder() {
+        let decoded = dpt("$SDDPT,012.50,X,A").unwrap();
+        assert_eq!(decoded.message_id(), "DPT");
+        assert_eq!(decoded.to_nmea().unwrap().to_string(), "$SDDPT,12.5,,A,*0E");
+    }
+
+    #[test]
+    fn test_derived_metadata() {
+        assert_eq!(Dpt::MANUFACTURER, None);
+        assert_eq!(Dpt::MESSAGE_ID, "DPT");
+        assert_eq!(
+            Dpt::FIELDS[0],
+            FieldMeta {
+                name: "depth",
+                index: 0,
+                ty: FieldType::Float,
+                unit: Some("meter"),
+                nullable: false,
+                option: false,
+                required: true,
+                repeated: false,
+            }
+        );
+        assert_eq!(Dpt::FIELDS[1].ty, FieldType::Enum(&["A", "V"]));
+        assert!(!Dpt::FIELDS[2].required);
+
+        assert_eq!(Svdy::MANUFACTURER, Some("SVD"));
+        let indices: Vec<_> = Svdy::FIELDS.iter().map(|field| field.index).collect();
+        assert_eq!(indices, (0..13).collect::<Vec<_>>());
+        assert_eq!(Svdy::FIELDS[12].name, "index");
+        assert_eq!(Svdy::FIELDS[12].ty, FieldType::Integer);
+    }
+}
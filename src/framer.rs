@@ -0,0 +1,500 @@
+use crate::{
+    config::ParserConfig,
+    sentences::error::ParseNMEA0183Error,
+    storage::{Buffer, TryPush},
+    NmeaParser, Sentence,
+};
+#[cfg(feature = "std")]
+use std::io::Read;
+
+/// Default upper bound on the length of a single framed sentence, in bytes.
+///
+/// The NMEA0183 standard limits sentences to 82 characters, but proprietary sentences such as
+/// `$PSVSW` routinely exceed that, so the default leaves plenty of headroom.
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 512;
+
+/// Result of scanning a byte buffer for the next sentence.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Scan {
+    /// Bytes `start..end` hold a complete sentence, `consumed` bytes can be dropped.
+    Frame {
+        skipped: usize,
+        start: usize,
+        end: usize,
+        consumed: usize,
+    },
+    /// A sentence exceeded the maximum line length and `consumed` bytes were thrown away.
+    TooLong { skipped: usize, consumed: usize },
+    /// No complete sentence yet, `skipped` bytes of garbage in front of it can be dropped.
+    Incomplete { skipped: usize },
+}
+
+fn is_start(b: u8) -> bool {
+    b == b'$' || b == b'!'
+}
+
+fn is_end(b: u8) -> bool {
+    b == b'\r' || b == b'\n'
+}
+
+/// Number of bytes in `bytes` that count as discarded, stray line endings are not counted.
+pub(crate) fn discarded(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|b| !is_end(**b)).count()
+}
+
+/// Parses a framed sentence, bytes that are not UTF-8 are a grammar error.
+pub(crate) fn parse_frame(
+    parser: &NmeaParser,
+    frame: &[u8],
+) -> Result<Sentence, ParseNMEA0183Error> {
+    match core::str::from_utf8(frame) {
+        Ok(line) => parser.parse_line(line),
+        Err(_) => Err(ParseNMEA0183Error::ParseGrammarError),
+    }
+}
+
+/// Finds the next sentence in `buffer`.
+///
+/// A sentence starts at `$` or `!`, or at the `\` of a TAG block in front of it, and ends at a
+/// CR, an LF or the start of the next sentence, so CRLF, LF-only and glued sentences are all
+/// handled.  Bytes in front of the sentence are reported as skipped.
+pub(crate) fn scan(buffer: &[u8], max_line_length: usize) -> Scan {
+    let mut from = 0;
+    let (start, body) = loop {
+        let Some(start) = buffer[from..]
+            .iter()
+            .position(|b| is_start(*b) || *b == b'\\')
+            .map(|i| from + i)
+        else {
+            return Scan::Incomplete {
+                skipped: buffer.len(),
+            };
+        };
+        if buffer[start] != b'\\' {
+            break (start, start + 1);
+        }
+
+        // Skip over the TAG block, a stray backslash is treated as line noise.
+        let close = buffer[start + 1..]
+            .iter()
+            .position(|b| *b == b'\\' || is_start(*b) || is_end(*b))
+            .map(|i| start + 1 + i);
+        match close {
+            Some(close) if buffer[close] == b'\\' => {
+                let delimiter = buffer.get(close + 1).copied();
+                break (
+                    start,
+                    close + 1 + usize::from(delimiter.is_some_and(is_start)),
+                );
+            }
+            Some(close) => from = close,
+            None => break (start, buffer.len()),
+        }
+    };
+
+    let end = buffer[body.min(buffer.len())..]
+        .iter()
+        .position(|b| is_start(*b) || is_end(*b))
+        .map(|i| body + i);
+
+    match end {
+        Some(end) if end - start > max_line_length => Scan::TooLong {
+            skipped: start,
+            consumed: end,
+        },
+        Some(end) => Scan::Frame {
+            skipped: start,
+            start,
+            end,
+            consumed: if is_end(buffer[end]) { end + 1 } else { end },
+        },
+        None if buffer.len() - start > max_line_length => Scan::TooLong {
+            skipped: start,
+            consumed: buffer.len(),
+        },
+        None => Scan::Incomplete { skipped: start },
+    }
+}
+
+/// Push-style sentence framer for raw bytes, for example from a serial port.
+///
+/// Feed it chunks of any size with [`Framer::push`] and pull decoded sentences out with
+/// [`Framer::next_sentence`].  Partial lines are kept until the rest arrives, line noise between
+/// sentences is skipped and lines longer than the maximum line length are dropped.
+///
+/// Without the `alloc` feature the buffer holds at most [`crate::storage::BUFFER_CAPACITY`]
+/// bytes, when it is full the oldest bytes are discarded to make room for new ones.
+///
+/// ```
+/// # #[cfg(feature = "wind")] {
+/// use oor_nmea0183::{framer::Framer, Sentence};
+///
+/// let mut framer = Framer::new();
+/// framer.push(b"\x00\xff$WIMWV,049,R,000.");
+/// assert!(framer.next_sentence().is_none());
+///
+/// framer.push(b"03,N,A*03\r\n");
+/// assert!(matches!(framer.next_sentence(), Some(Ok(Sentence::Mwv(_)))));
+/// assert_eq!(framer.discarded_bytes(), 2);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Framer {
+    parser: NmeaParser,
+    buffer: Buffer,
+    max_line_length: usize,
+    discarded: usize,
+}
+
+impl Default for Framer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Framer {
+    pub fn new() -> Self {
+        Self::with_max_line_length(DEFAULT_MAX_LINE_LENGTH)
+    }
+
+    pub fn with_max_line_length(max_line_length: usize) -> Self {
+        Self::with_parser(NmeaParser::default(), max_line_length)
+    }
+
+    /// Creates a framer that decodes sentences according to `config`.
+    pub fn with_config(config: ParserConfig) -> Self {
+        Self::with_parser(NmeaParser::with_config(config), DEFAULT_MAX_LINE_LENGTH)
+    }
+
+    pub fn with_parser(parser: NmeaParser, max_line_length: usize) -> Self {
+        Framer {
+            parser,
+            buffer: Buffer::new(),
+            max_line_length,
+            discarded: 0,
+        }
+    }
+
+    /// Appends raw bytes to the internal buffer.
+    pub fn push(&mut self, data: &[u8]) {
+        #[cfg(not(feature = "alloc"))]
+        let data = self.make_room(data);
+        let _ = self.buffer.try_extend_from_slice(data);
+    }
+
+    /// Discards the oldest bytes that would not fit next to `data`, and the start of `data` if
+    /// it does not fit on its own.
+    #[cfg(not(feature = "alloc"))]
+    fn make_room<'a>(&mut self, data: &'a [u8]) -> &'a [u8] {
+        let capacity = self.buffer.capacity();
+        let data = match data.len().checked_sub(capacity) {
+            Some(excess) => {
+                self.discarded += discarded(&data[..excess]);
+                &data[excess..]
+            }
+            None => data,
+        };
+        let overflow = (self.buffer.len() + data.len()).saturating_sub(capacity);
+        self.discard(overflow);
+        self.consume(overflow);
+        data
+    }
+
+    /// Total number of bytes thrown away so far, either because they were not part of any
+    /// sentence or because the sentence was too long.  Line endings are not counted.
+    pub fn discarded_bytes(&self) -> usize {
+        self.discarded
+    }
+
+    /// Number of bytes currently buffered while waiting for the end of a sentence.
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Counts the first `len` buffered bytes as discarded, except for stray line endings.
+    fn discard(&mut self, len: usize) {
+        self.discarded += discarded(&self.buffer[..len]);
+    }
+
+    /// Drops the first `len` buffered bytes.
+    fn consume(&mut self, len: usize) {
+        self.buffer.copy_within(len.., 0);
+        self.buffer.truncate(self.buffer.len() - len);
+    }
+
+    /// Returns the next complete sentence, or `None` if more data is needed.
+    pub fn next_sentence(&mut self) -> Option<Result<Sentence, ParseNMEA0183Error>> {
+        match scan(&self.buffer, self.max_line_length) {
+            Scan::Frame {
+                skipped,
+                start,
+                end,
+                consumed,
+            } => {
+                self.discard(skipped);
+                let sentence = self.parse_frame(start, end);
+                self.consume(consumed);
+                Some(sentence)
+            }
+            Scan::TooLong { skipped, consumed } => {
+                self.discard(skipped);
+                self.discarded += consumed - skipped;
+                self.consume(consumed);
+                Some(Err(ParseNMEA0183Error::SentenceTooLong(
+                    self.max_line_length,
+                )))
+            }
+            Scan::Incomplete { skipped } => {
+                self.discard(skipped);
+                self.consume(skipped);
+                None
+            }
+        }
+    }
+
+    fn parse_frame(&self, start: usize, end: usize) -> Result<Sentence, ParseNMEA0183Error> {
+        parse_frame(&self.parser, &self.buffer[start..end])
+    }
+
+    /// Parses whatever is left in the buffer as a final, unterminated sentence.
+    ///
+    /// Call this once the underlying stream has ended.
+    pub fn finish(&mut self) -> Option<Result<Sentence, ParseNMEA0183Error>> {
+        if let Some(sentence) = self.next_sentence() {
+            return Some(sentence);
+        }
+        if self.buffer.is_empty() {
+            return None;
+        }
+        let sentence = self.parse_frame(0, self.buffer.len());
+        self.buffer.clear();
+        Some(sentence)
+    }
+}
+
+/// Iterator over the sentences read from any [`Read`] implementation, such as a serial port,
+/// a `TcpStream` or a log file.
+///
+/// Each item is the result of decoding one sentence, so a bad sentence does not end the
+/// iteration.  I/O errors are returned once, after which the iterator only drains what is left
+/// in its buffer.
+///
+/// ```
+/// use oor_nmea0183::framer::NmeaReader;
+///
+/// let log = "$WIMWV,049,R,000.03,N,A*03\n$WIMWV,180,T,000.11,N,A*02\n";
+/// let sentences: Vec<_> = NmeaReader::new(log.as_bytes()).collect();
+/// assert_eq!(sentences.len(), 2);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct NmeaReader<R> {
+    inner: R,
+    framer: Framer,
+    chunk: Vec<u8>,
+    eof: bool,
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> NmeaReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self::with_framer(inner, Framer::new())
+    }
+
+    pub fn with_framer(inner: R, framer: Framer) -> Self {
+        NmeaReader {
+            inner,
+            framer,
+            chunk: vec![0; 1024],
+            eof: false,
+        }
+    }
+
+    /// See [`Framer::discarded_bytes`].
+    pub fn discarded_bytes(&self) -> usize {
+        self.framer.discarded_bytes()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> Iterator for NmeaReader<R> {
+    type Item = Result<Sentence, ParseNMEA0183Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(sentence) = self.framer.next_sentence() {
+                return Some(sentence);
+            }
+            if self.eof {
+                return self.framer.finish();
+            }
+            match self.inner.read(&mut self.chunk) {
+                Ok(0) => self.eof = true,
+                Ok(n) => self.framer.push(&self.chunk[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.eof = true;
+                    return Some(Err(e.into()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std", feature = "all-sentences"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan() {
+        assert_eq!(
+            scan(b"xx$GPGGA,1*00\r\n", 82),
+            Scan::Frame {
+                skipped: 2,
+                start: 2,
+                end: 13,
+                consumed: 14
+            }
+        );
+        assert_eq!(
+            scan(b"$A,1$B,2", 82),
+            Scan::Frame {
+                skipped: 0,
+                start: 0,
+                end: 4,
+                consumed: 4
+            }
+        );
+        assert_eq!(scan(b"noise", 82), Scan::Incomplete { skipped: 5 });
+        assert_eq!(scan(b"n$GPGGA,", 82), Scan::Incomplete { skipped: 1 });
+        assert_eq!(
+            scan(b"$GPGGA,0123456789", 8),
+            Scan::TooLong {
+                skipped: 0,
+                consumed: 17
+            }
+        );
+    }
+
+    #[test]
+    fn test_scan_tag_block() {
+        assert_eq!(
+            scan(b"\\s:GP01*00\\$GPGGA,1*00\r\n", 82),
+            Scan::Frame {
+                skipped: 0,
+                start: 0,
+                end: 22,
+                consumed: 23
+            }
+        );
+        assert_eq!(
+            scan(b"x\\noise$GPGGA,1*00\r\n", 82),
+            Scan::Frame {
+                skipped: 7,
+                start: 7,
+                end: 18,
+                consumed: 19
+            }
+        );
+        assert_eq!(scan(b"\\s:GP01*00", 82), Scan::Incomplete { skipped: 0 });
+        assert_eq!(scan(b"\\s:GP01*00\\", 82), Scan::Incomplete { skipped: 0 });
+    }
+
+    #[test]
+    fn test_framer_tag_block() {
+        let mut framer = Framer::new();
+        framer.push(b"\\s:GP01,c:1700000000*2C\\$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56\r\n");
+        match framer.next_sentence() {
+            Some(Ok(Sentence::Gga(gga))) => {
+                assert_eq!(gga.tag_block.unwrap().source, Some("GP01".to_string()))
+            }
+            _ => panic!("Expected Gga"),
+        }
+        assert_eq!(framer.discarded_bytes(), 0);
+    }
+
+    #[test]
+    fn test_framer_line_endings() {
+        let mut framer = Framer::new();
+        framer.push(b"$WIMWV,049,R,000.03,N,A*03\r\n$WIMWV,180,T,000.11,N,A*02\n");
+        framer.push(b"$WIMWV,049,R,000.03,N,A*03\r$WIMWV,180,T,000.11,N,A*02");
+        framer.push(b"$WIMWV,049,R,000.03,N,A*03\n");
+
+        for _ in 0..5 {
+            assert!(matches!(framer.next_sentence(), Some(Ok(Sentence::Mwv(_)))));
+        }
+        assert!(framer.next_sentence().is_none());
+        assert_eq!(framer.discarded_bytes(), 0);
+    }
+
+    #[test]
+    fn test_framer_byte_at_a_time() {
+        let input =
+            b"\x00\x13garbage$WIMWV,049,R,000.03,N,A*03\r\nmore noise\r\n$PSVSI,WINDMILL*73\r\n";
+        let mut framer = Framer::new();
+        let mut sentences = vec![];
+        for b in input {
+            framer.push(&[*b]);
+            while let Some(sentence) = framer.next_sentence() {
+                sentences.push(sentence.unwrap());
+            }
+        }
+
+        assert_eq!(sentences.len(), 2);
+        assert!(matches!(sentences[0], Sentence::Mwv(_)));
+        assert!(matches!(sentences[1], Sentence::Svsi(_)));
+        assert_eq!(framer.discarded_bytes(), 9 + 10);
+    }
+
+    #[test]
+    fn test_framer_resync_after_corruption() {
+        let mut framer = Framer::new();
+        framer.push(b"$WIMW$WIMWV,180,T,000.11,N,A*02\r\n");
+
+        assert!(framer.next_sentence().unwrap().is_err());
+        assert!(matches!(framer.next_sentence(), Some(Ok(Sentence::Mwv(_)))));
+        assert!(framer.next_sentence().is_none());
+    }
+
+    #[test]
+    fn test_framer_max_line_length() {
+        let mut framer = Framer::with_max_line_length(32);
+        framer.push(b"$PSVSS,0.0000,0.0000,0.0000,0.0000,0.0000");
+        assert!(matches!(
+            framer.next_sentence(),
+            Some(Err(ParseNMEA0183Error::SentenceTooLong(32)))
+        ));
+        assert_eq!(framer.buffered_bytes(), 0);
+
+        framer.push(b",0.0000*55\r\n$WIMWV,049,R,000.03,N,A*03\r\n");
+        assert!(matches!(framer.next_sentence(), Some(Ok(Sentence::Mwv(_)))));
+        assert_eq!(framer.discarded_bytes(), 41 + 10);
+    }
+
+    #[test]
+    fn test_framer_config() {
+        let mut framer = Framer::with_config(ParserConfig::strict());
+        framer.push(b"$WIMWV,049,R,000.03,N,A\r\n$WIMWV,049,R,000.03,N,A*03\r\n");
+        assert!(matches!(
+            framer.next_sentence(),
+            Some(Err(ParseNMEA0183Error::MissingChecksum))
+        ));
+        assert!(matches!(framer.next_sentence(), Some(Ok(Sentence::Mwv(_)))));
+    }
+
+    #[test]
+    fn test_reader() {
+        let input = "$WIMWV,049,R,000.03,N,A*03\r\nnoise$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56\r\n$PSVSI,WINDMILL*73";
+        let mut reader = NmeaReader::new(input.as_bytes());
+
+        assert!(matches!(reader.next(), Some(Ok(Sentence::Mwv(_)))));
+        assert!(matches!(reader.next(), Some(Ok(Sentence::Gga(_)))));
+        assert!(matches!(reader.next(), Some(Ok(Sentence::Svsi(_)))));
+        assert!(reader.next().is_none());
+        assert_eq!(reader.discarded_bytes(), 5);
+    }
+}
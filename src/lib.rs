@@ -1,126 +1,775 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+// Without an allocator field errors cannot be boxed and are kept inline.
+#![cfg_attr(
+    not(feature = "alloc"),
+    allow(clippy::result_large_err, clippy::large_enum_variant)
+)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+// Lets the code generated by the derive macros name this crate from inside it too.
+extern crate self as oor_nmea0183;
+
+#[cfg(not(any(feature = "alloc", feature = "heapless")))]
+compile_error!("oor-nmea0183 needs the `alloc` or the `heapless` feature to store sentences");
+
+pub mod catalogue;
+#[cfg(feature = "async")]
+pub mod codec;
+pub mod config;
+pub mod conformance;
+mod escape;
+pub mod field;
+pub mod framer;
+#[cfg(feature = "alloc")]
+pub mod lossless;
+#[cfg(feature = "alloc")]
+pub mod registry;
+mod scanner;
 pub mod sentences;
+/// The strings and vectors sentences are stored in, heap allocated with the `alloc` feature and
+/// of fixed capacity with only the `heapless` feature.
+pub mod storage;
+#[cfg(any(feature = "proptest", all(test, feature = "std")))]
+pub mod strategies;
+pub mod tag_block;
+pub mod talker;
+#[cfg(feature = "seaview")]
 use crate::sentences::seaview::{
     psvdy::Svdy, psvsd::Svsd, psvsh::Svsh, psvsi::Svsi, psvss::Svss, psvst::Svst, psvsv::Svsv,
     psvsw::Svsw,
 };
-use pest::Parser;
+use config::{ChecksumPolicy, EncodeOptions, ParserConfig, UnknownSentencePolicy};
+use core::{
+    fmt::{self, Write},
+    str::FromStr,
+};
+use field::FieldType;
+#[cfg(feature = "alloc")]
 use pest_derive::Parser;
+#[cfg(feature = "alloc")]
+use registry::{Custom, SentenceRegistry};
+#[cfg(feature = "gnss")]
+use sentences::gga::Gga;
+#[cfg(feature = "wind")]
+use sentences::mwv::Mwv;
+#[cfg(feature = "gill")]
+use sentences::pgilt::Gilt;
+#[cfg(feature = "transducer")]
+use sentences::xdr::Xdr;
 use sentences::{
-    error::ParseNMEA0183Error, gga::Gga, hdm::Hdm, hdt::Hdt, mwv::Mwv, pgilt::Gilt, xdr::Xdr,
+    error::{FieldError, FieldErrorKind, ParseNMEA0183Error},
+    query::Query,
+    DecodeContext, DecodeMode,
 };
+#[cfg(feature = "heading")]
+use sentences::{hdm::Hdm, hdt::Hdt};
+use storage::{format, Box, Cow, List, ToString, Vec};
+use tag_block::TagBlock;
+use talker::TalkerId;
+
+pub use oor_nmea0183_derive::{NmeaEnum, NmeaSentence};
+pub use sentences::NmeaSentence;
+
+/// A decoded sentence.
+///
+/// Each family of sentences is behind a Cargo feature, `gnss`, `wind`, `heading`, `transducer`,
+/// `gill` and `seaview`, all enabled by default through `all-sentences`.  The sentences of a
+/// disabled family have no variant and are decoded as [`Sentence::Unknown`].
+///
+/// ### Serde:
+/// With the `serde` feature a sentence is serialized as an object with the name of its variant
+/// as `type`, next to the members of the sentence struct:
+/// - Talker IDs are strings as written in a sentence, `"WI"`, or `"PGIL"` for proprietary ones.
+/// - Enums are written as in a sentence, e.g. `"N"` for knots or `"1"` for a GPS fix.
+///   Transducer readings are objects with their type letter as `type`, e.g.
+///   `{"type":"C","reading":23.1,...}`.
+/// - Missing values are `null`.
+/// - `uom` quantities are plain numbers in the unit of their field, e.g. degrees for the heading
+///   of a `PSVDY`.
+///
+/// [`catalogue::json_schema`] describes the objects sentences are serialized as.
+/// - Timestamps are ISO 8601 strings without a time zone, `"2023-01-12T12:26:27"`.
+/// - [`Sentence::Custom`] is written as the sentence it was decoded from,
+///   `{"type":"Custom","nmea":{...}}`, and cannot be deserialized.
+///
+/// ```
+/// # #[cfg(all(feature = "serde", feature = "wind"))] {
+/// use oor_nmea0183::{NmeaParser, Sentence};
+///
+/// let mwv = NmeaParser::parse("$WIMWV,049,R,000.03,N,A*03").unwrap();
+/// let json = serde_json::to_string(&mwv).unwrap();
+/// assert_eq!(
+///     json,
+///     r#"{"type":"Mwv","talker_id":"WI","message_id":"MWV","tag_block":null,"wind_direction_deg":49,"wind_dir_type":"R","wind_speed":0.03,"wind_speed_units":"N","acceptable":"A"}"#
+/// );
+/// assert_eq!(serde_json::from_str::<Sentence>(&json).unwrap(), mwv);
+/// # }
+/// ```
+pub type Sentence = SentenceRef<'static>;
 
-#[derive(Debug)]
-pub enum Sentence {
-    Unknown,
+/// A decoded sentence that may borrow from the line it was parsed from, see [`Sentence`].
+///
+/// Returned by [`NmeaParser::parse_ref`], only [`SentenceRef::Unknown`] holds data from the
+/// line, the sentence structs of the other variants keep nothing but decoded values.  Use
+/// [`SentenceRef::into_owned`] when the sentence needs to outlive the line.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+pub enum SentenceRef<'a> {
+    /// A sentence without a decoder, kept so that it can still be forwarded, logged or decoded
+    /// later.  `raw` is the line it was parsed from, it is only known when the sentence was
+    /// parsed by an [`NmeaParser`].
+    Unknown {
+        nmea: Nmea<'a>,
+        raw: Option<Cow<'a, str>>,
+    },
+    #[cfg(feature = "wind")]
     Mwv(sentences::mwv::Mwv),
+    #[cfg(feature = "transducer")]
     Xdr(sentences::xdr::Xdr),
+    #[cfg(feature = "gnss")]
     Gga(sentences::gga::Gga),
+    #[cfg(feature = "heading")]
     Hdm(sentences::hdm::Hdm),
+    #[cfg(feature = "heading")]
     Hdt(sentences::hdt::Hdt),
+    #[cfg(feature = "gill")]
     Gilt(sentences::pgilt::Gilt),
+    #[cfg(feature = "seaview")]
     Svdy(sentences::seaview::psvdy::Svdy),
+    #[cfg(feature = "seaview")]
     Svss(sentences::seaview::psvss::Svss),
+    #[cfg(feature = "seaview")]
     Svsd(sentences::seaview::psvsd::Svsd),
+    #[cfg(feature = "seaview")]
     Svst(sentences::seaview::psvst::Svst),
+    #[cfg(feature = "seaview")]
     Svsi(sentences::seaview::psvsi::Svsi),
+    #[cfg(feature = "seaview")]
     Svsv(sentences::seaview::psvsv::Svsv),
+    #[cfg(feature = "seaview")]
     Svsh(sentences::seaview::psvsh::Svsh),
+    #[cfg(feature = "seaview")]
     Svsw(sentences::seaview::psvsw::Svsw),
+    Query(sentences::query::Query),
+    /// A sentence decoded by a decoder from a [`SentenceRegistry`].
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    Custom(Custom),
 }
 
+/// The grammar of a sentence.  [`NmeaParser`] splits sentences with a faster hand-written
+/// scanner that accepts exactly the same sentences, this grammar remains the reference for it.
+#[cfg(feature = "alloc")]
 #[derive(Parser)]
 #[grammar = "nmea.pest"] // Relative path to the grammar file
 pub struct NmeaPest;
 
-#[derive(Debug)]
-pub struct Nmea {
-    pub talker_id: String,
-    pub message_id: String,
-    pub fields: Vec<String>,
+/// The character a sentence starts with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Delimiter {
+    /// `$`, a parametric sentence with plain comma separated fields.
+    Parametric,
+    /// `!`, an encapsulation sentence carrying an armoured payload, for example AIS `!AIVDM`.
+    Encapsulation,
 }
 
-pub struct NmeaParser {}
-impl NmeaParser {
-    pub fn parse(nmea_sentence: &str) -> Result<Sentence, ParseNMEA0183Error> {
-        let nmea = NmeaParser::to_nmea(nmea_sentence.trim())?;
-        Ok(match nmea.message_id.as_ref() {
-            "MWV" => Sentence::Mwv(Mwv::try_from(nmea)?),
-            "GGA" => Sentence::Gga(Gga::try_from(nmea)?),
-            "XDR" => Sentence::Xdr(Xdr::try_from(nmea)?),
-            "HDM" => Sentence::Hdm(Hdm::try_from(nmea)?),
-            "HDT" => Sentence::Hdt(Hdt::try_from(nmea)?),
-            "GILT" => Sentence::Gilt(Gilt::try_from(nmea)?),
-            "SVDY" => Sentence::Svdy(Svdy::try_from(nmea)?),
-            "SVSS" => Sentence::Svss(Svss::try_from(nmea)?),
-            "SVSD" => Sentence::Svsd(Svsd::try_from(nmea)?),
-            "SVST" => Sentence::Svst(Svst::try_from(nmea)?),
-            "SVSI" => Sentence::Svsi(Svsi::try_from(nmea)?),
-            "SVSV" => Sentence::Svsv(Svsv::try_from(nmea)?),
-            "SVSH" => Sentence::Svsh(Svsh::try_from(nmea)?),
-            "SVSW" => Sentence::Svsw(Svsw::try_from(nmea)?),
-            _ => Sentence::Unknown,
-        })
+impl Delimiter {
+    pub fn as_char(&self) -> char {
+        match self {
+            Delimiter::Parametric => '$',
+            Delimiter::Encapsulation => '!',
+        }
     }
+}
 
-    fn verify_checksum(sentence: &str, checksum: String) -> Result<(), ParseNMEA0183Error> {
-        let sentence = &sentence[1..sentence.len() - 3];
-        let checksum = u8::from_str_radix(&checksum[1..checksum.len()], 16)?;
-        let mut temp: u8 = 0;
-        for b in sentence.as_bytes() {
-            temp ^= b;
+/// A parsed but not yet decoded NMEA0183 sentence.
+///
+/// The message ID and fields borrow from the input line, so splitting a sentence does not
+/// allocate any strings for them, only fields containing `^HH` escapes are decoded into an
+/// owned string.  For proprietary sentences the manufacturer mnemonic is part of the
+/// [`TalkerId`] and the message ID is what follows it, e.g. `T` for `$PGILT`.  Use
+/// [`Nmea::into_owned`] when the sentence needs to outlive the line it was parsed from.
+///
+/// Escapes are read as UTF-8, falling back to Latin-1 for a field that is not valid UTF-8,
+/// such as `25^B0C` for `25°C`.  The fallback is one way: the field is written back as UTF-8,
+/// `25^C2^B0C`, so keep the line, e.g. with `NmeaParser::parse_lossless`, where the original
+/// bytes matter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Nmea<'a> {
+    pub tag_block: Option<TagBlock>,
+    pub delimiter: Delimiter,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'a, str>,
+    pub fields: Vec<Cow<'a, str>>,
+}
+
+impl Nmea<'_> {
+    /// Parses field `index` as `T`, reporting a failure as a [`FieldError`] naming the sentence
+    /// and the field, or as [`ParseNMEA0183Error::MissingFields`] when the sentence is too short.
+    pub fn parse_field<T>(&self, index: usize, name: &'static str) -> Result<T, ParseNMEA0183Error>
+    where
+        T: FromStr,
+        T::Err: Into<FieldErrorKind>,
+    {
+        self.fields
+            .get(index)
+            .ok_or(ParseNMEA0183Error::MissingFields((index + 1) as u16))?
+            .parse::<T>()
+            .map_err(|e| self.field_error(index, name, e.into()))
+    }
+
+    /// Builds the error for a field of this sentence that could not be decoded.
+    pub fn field_error(
+        &self,
+        index: usize,
+        name: &'static str,
+        kind: FieldErrorKind,
+    ) -> ParseNMEA0183Error {
+        ParseNMEA0183Error::InvalidField(Box::new(FieldError {
+            talker_id: self.talker_id.clone(),
+            message_id: self.message_id.to_string(),
+            index,
+            name: Some(name),
+            raw: self
+                .fields
+                .get(index)
+                .map(|f| f.to_string())
+                .unwrap_or_default(),
+            kind,
+            line: None,
+        }))
+    }
+
+    /// Copies any borrowed data so the sentence no longer references the input line.
+    pub fn into_owned(self) -> Nmea<'static> {
+        Nmea {
+            tag_block: self.tag_block,
+            delimiter: self.delimiter,
+            talker_id: self.talker_id,
+            message_id: Cow::Owned(self.message_id.into_owned()),
+            fields: self
+                .fields
+                .into_iter()
+                .map(|f| Cow::Owned(f.into_owned()))
+                .collect(),
         }
+    }
+}
 
-        if checksum != temp {
-            return Err(sentences::error::ParseNMEA0183Error::NMEA0183ChecksumError);
+/// Writes the sentence back out, including its TAG block, with a freshly computed checksum.
+/// Reserved characters in fields are written as `^HH` escapes, as are the spaces at the start
+/// of the first field and at the end of the last one, other characters outside printable ASCII
+/// as the escapes of their UTF-8 bytes.  The line ending is not included.
+impl fmt::Display for Nmea<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, None)
+    }
+}
+
+impl Nmea<'_> {
+    /// Writes the sentence like [`fmt::Display`], rounding the fields the catalogue describes as
+    /// numbers that are not integers to `precision` decimals.
+    fn write(&self, f: &mut fmt::Formatter<'_>, precision: Option<usize>) -> fmt::Result {
+        if let Some(tag_block) = &self.tag_block {
+            write!(f, "{}", tag_block)?;
+        }
+        write!(f, "{}", self.delimiter.as_char())?;
+        let info = precision
+            .filter(|_| self.delimiter == Delimiter::Parametric)
+            .and_then(|_| catalogue::find(self.talker_id.manufacturer(), &self.message_id));
+        let mut body = ChecksumWriter::new(&mut *f);
+        write!(body, "{}{},", self.talker_id, self.message_id)?;
+        for (i, field) in self.fields.iter().enumerate() {
+            if i > 0 {
+                body.write_char(',')?;
+            }
+            let (leading, field, trailing) =
+                escape::split_spaces(field, i == 0, i + 1 == self.fields.len());
+            (0..leading).try_for_each(|_| body.write_str("^20"))?;
+            let number = info
+                .and_then(|info| info.field(i))
+                .filter(|meta| meta.ty == FieldType::Float);
+            match precision
+                .zip(number)
+                .map(|(precision, meta)| (precision, meta.unit))
+            {
+                // A time of day is never rounded, `235959.6` would become `240000`.
+                Some((_, Some("hhmmss"))) | None => escape::write_escaped(&mut body, field)?,
+                Some((precision, Some("ddmm" | "dddmm"))) => {
+                    write_rounded_minutes(&mut body, field, precision)?
+                }
+                Some((precision, _)) => write_rounded(&mut body, field, precision)?,
+            }
+            (0..trailing).try_for_each(|_| body.write_str("^20"))?;
+        }
+        let checksum = body.checksum();
+        write!(f, "*{:02X}", checksum)
+    }
+}
+
+/// Writes the number in `field` with `precision` decimals and at least as many digits before
+/// the decimal point as it has.  Fields that are not numbers are written unchanged.
+fn write_rounded(f: &mut impl Write, field: &str, precision: usize) -> fmt::Result {
+    let Ok(value) = field.parse::<f64>() else {
+        return escape::write_escaped(f, field);
+    };
+    let whole = field.find('.').unwrap_or(field.len());
+    let width = whole + if precision > 0 { precision + 1 } else { 0 };
+    if field.starts_with('+') {
+        write!(f, "{:+0width$.precision$}", value)
+    } else {
+        write!(f, "{:0width$.precision$}", value)
+    }
+}
+
+/// Writes the `ddmm.mm` or `dddmm.mm` angle in `field` with `precision` decimals of minutes,
+/// carrying minutes that round up to 60 into the degrees.
+fn write_rounded_minutes(f: &mut impl Write, field: &str, precision: usize) -> fmt::Result {
+    let Some(value) = field.parse::<f64>().ok().filter(|value| value.is_finite()) else {
+        return escape::write_escaped(f, field);
+    };
+    let digits = field.trim_start_matches(['+', '-']);
+    let sign = &field[..field.len() - digits.len()];
+    let whole = digits.find('.').unwrap_or(digits.len());
+    let value = value.abs();
+    let mut degrees = (value / 100.0) as u64;
+    let width = 2 + if precision > 0 { precision + 1 } else { 0 };
+    let mut minutes = format!("{:0width$.precision$}", value - degrees as f64 * 100.0);
+    if minutes.starts_with("60") {
+        degrees += 1;
+        minutes = format!("{:0width$.precision$}", 0.0);
+    }
+    let width = whole.saturating_sub(2);
+    write!(f, "{}{:0width$}{}", sign, degrees, minutes)
+}
+
+/// A sentence encoded as a line following [`EncodeOptions`], returned by
+/// [`NmeaSentence::encode`].  Write it out with [`fmt::Display`].
+#[derive(Debug, Clone)]
+pub struct Encoded {
+    nmea: Nmea<'static>,
+    options: EncodeOptions,
+}
+
+impl Encoded {
+    pub(crate) fn new(nmea: Nmea<'static>, options: &EncodeOptions) -> Self {
+        Encoded {
+            nmea,
+            options: *options,
         }
+    }
+
+    pub fn nmea(&self) -> &Nmea<'static> {
+        &self.nmea
+    }
+}
 
+impl fmt::Display for Encoded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.nmea.write(f, self.options.precision)?;
+        if self.options.crlf {
+            f.write_str("\r\n")?;
+        }
         Ok(())
     }
+}
+
+impl NmeaSentence for Nmea<'_> {
+    fn talker_id(&self) -> &TalkerId {
+        &self.talker_id
+    }
 
-    fn to_nmea(nmea_sentence: &str) -> Result<Nmea, ParseNMEA0183Error> {
-        let parsed = match NmeaPest::parse(Rule::NMEA, nmea_sentence) {
-            Ok(p) => p,
-            Err(_) => return Err(ParseNMEA0183Error::ParseGrammarError),
+    fn message_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.message_id)
+    }
+
+    fn tag_block(&self) -> Option<&TagBlock> {
+        self.tag_block.as_ref()
+    }
+
+    fn to_nmea(&self) -> Result<Nmea<'static>, ParseNMEA0183Error> {
+        Ok(self.clone().into_owned())
+    }
+}
+
+/// XOR checksum of every byte in `data`, as used by NMEA0183 sentences and TAG blocks.
+pub fn checksum(data: &str) -> u8 {
+    data.bytes().fold(0, |acc, b| acc ^ b)
+}
+
+/// Passes everything written through to `inner` while computing its [`checksum`], so that a
+/// sentence can be written without building it in a string first.
+pub(crate) struct ChecksumWriter<W> {
+    inner: W,
+    checksum: u8,
+}
+
+impl<W: fmt::Write> ChecksumWriter<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        ChecksumWriter { inner, checksum: 0 }
+    }
+
+    pub(crate) fn checksum(&self) -> u8 {
+        self.checksum
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for ChecksumWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.checksum ^= checksum(s);
+        self.inner.write_str(s)
+    }
+}
+
+impl<'a> TryFrom<Nmea<'a>> for SentenceRef<'a> {
+    type Error = ParseNMEA0183Error;
+    fn try_from(nmea: Nmea<'a>) -> Result<Self, Self::Error> {
+        SentenceRef::decode(nmea, &mut DecodeContext::default())
+    }
+}
+
+/// Delegates to the sentence held by the variant.
+impl NmeaSentence for SentenceRef<'_> {
+    fn talker_id(&self) -> &TalkerId {
+        self.as_nmea_sentence().talker_id()
+    }
+
+    fn message_id(&self) -> Cow<'_, str> {
+        self.as_nmea_sentence().message_id()
+    }
+
+    fn tag_block(&self) -> Option<&TagBlock> {
+        self.as_nmea_sentence().tag_block()
+    }
+
+    #[cfg(feature = "chrono")]
+    fn timestamp(&self) -> Option<chrono::NaiveDateTime> {
+        self.as_nmea_sentence().timestamp()
+    }
+
+    fn to_nmea(&self) -> Result<Nmea<'static>, ParseNMEA0183Error> {
+        self.as_nmea_sentence().to_nmea()
+    }
+}
+
+impl<'a> SentenceRef<'a> {
+    fn as_nmea_sentence(&self) -> &dyn NmeaSentence {
+        match self {
+            SentenceRef::Unknown { nmea, .. } => nmea,
+            #[cfg(feature = "wind")]
+            SentenceRef::Mwv(sentence) => sentence,
+            #[cfg(feature = "transducer")]
+            SentenceRef::Xdr(sentence) => sentence,
+            #[cfg(feature = "gnss")]
+            SentenceRef::Gga(sentence) => sentence,
+            #[cfg(feature = "heading")]
+            SentenceRef::Hdm(sentence) => sentence,
+            #[cfg(feature = "heading")]
+            SentenceRef::Hdt(sentence) => sentence,
+            #[cfg(feature = "gill")]
+            SentenceRef::Gilt(sentence) => sentence,
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svdy(sentence) => sentence,
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svss(sentence) => sentence,
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svsd(sentence) => sentence,
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svst(sentence) => sentence,
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svsi(sentence) => sentence,
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svsv(sentence) => sentence,
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svsh(sentence) => sentence,
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svsw(sentence) => sentence,
+            SentenceRef::Query(sentence) => sentence,
+            #[cfg(feature = "alloc")]
+            SentenceRef::Custom(sentence) => sentence,
         }
-        .next()
-        .unwrap();
+    }
 
-        let mut talker_id = String::default();
-        let mut message_type = String::default();
-        let mut fields = Vec::new();
-        let mut checksum = None;
-
-        for pair in parsed.into_inner() {
-            // println!("{:?}", pair);
-            match pair.as_rule() {
-                Rule::talker_id => talker_id = pair.as_str().to_string(),
-                Rule::message_type => message_type = pair.as_str().to_string(),
-                Rule::fields => {
-                    fields = pair
-                        .as_str()
-                        .split(',')
-                        .map(|s| s.to_string())
-                        .collect::<Vec<_>>();
-                }
-                Rule::checksum => checksum = Some(pair.as_str().to_string()),
-                _ => {}
+    /// Decodes a parsed sentence, `ctx` decides whether invalid field values reject it.
+    #[cfg_attr(
+        not(any(
+            feature = "gnss",
+            feature = "wind",
+            feature = "heading",
+            feature = "transducer",
+            feature = "gill",
+            feature = "seaview"
+        )),
+        allow(unused_variables)
+    )]
+    pub fn decode(nmea: Nmea<'a>, ctx: &mut DecodeContext) -> Result<Self, ParseNMEA0183Error> {
+        Ok(
+            match (nmea.talker_id.manufacturer(), nmea.message_id.as_ref()) {
+                #[cfg(feature = "wind")]
+                (None, "MWV") => SentenceRef::Mwv(Mwv::decode(nmea, ctx)?),
+                #[cfg(feature = "gnss")]
+                (None, "GGA") => SentenceRef::Gga(Gga::decode(nmea, ctx)?),
+                #[cfg(feature = "transducer")]
+                (None, "XDR") => SentenceRef::Xdr(Xdr::decode(nmea, ctx)?),
+                #[cfg(feature = "heading")]
+                (None, "HDM") => SentenceRef::Hdm(Hdm::decode(nmea, ctx)?),
+                #[cfg(feature = "heading")]
+                (None, "HDT") => SentenceRef::Hdt(Hdt::decode(nmea, ctx)?),
+                #[cfg(feature = "gill")]
+                (Some("GIL"), "T") => SentenceRef::Gilt(Gilt::decode(nmea, ctx)?),
+                #[cfg(feature = "seaview")]
+                (Some("SVD"), "Y") => SentenceRef::Svdy(Svdy::decode(nmea, ctx)?),
+                #[cfg(feature = "seaview")]
+                (Some("SVS"), "S") => SentenceRef::Svss(Svss::try_from(nmea)?),
+                #[cfg(feature = "seaview")]
+                (Some("SVS"), "D") => SentenceRef::Svsd(Svsd::decode(nmea, ctx)?),
+                #[cfg(feature = "seaview")]
+                (Some("SVS"), "T") => SentenceRef::Svst(Svst::decode(nmea, ctx)?),
+                #[cfg(feature = "seaview")]
+                (Some("SVS"), "I") => SentenceRef::Svsi(Svsi::decode(nmea, ctx)?),
+                #[cfg(feature = "seaview")]
+                (Some("SVS"), "V") => SentenceRef::Svsv(Svsv::decode(nmea, ctx)?),
+                #[cfg(feature = "seaview")]
+                (Some("SVS"), "H") => SentenceRef::Svsh(Svsh::decode(nmea, ctx)?),
+                #[cfg(feature = "seaview")]
+                (Some("SVS"), "W") => SentenceRef::Svsw(Svsw::decode(nmea, ctx)?),
+                _ if Query::is_query(&nmea) => SentenceRef::Query(Query::try_from(nmea)?),
+                _ => SentenceRef::Unknown { nmea, raw: None },
+            },
+        )
+    }
+
+    /// Copies any borrowed data so the sentence no longer references the input line.
+    pub fn into_owned(self) -> Sentence {
+        match self {
+            SentenceRef::Unknown { nmea, raw } => Sentence::Unknown {
+                nmea: nmea.into_owned(),
+                raw: raw.map(|raw| Cow::Owned(raw.into_owned())),
+            },
+            #[cfg(feature = "wind")]
+            SentenceRef::Mwv(sentence) => Sentence::Mwv(sentence),
+            #[cfg(feature = "transducer")]
+            SentenceRef::Xdr(sentence) => Sentence::Xdr(sentence),
+            #[cfg(feature = "gnss")]
+            SentenceRef::Gga(sentence) => Sentence::Gga(sentence),
+            #[cfg(feature = "heading")]
+            SentenceRef::Hdm(sentence) => Sentence::Hdm(sentence),
+            #[cfg(feature = "heading")]
+            SentenceRef::Hdt(sentence) => Sentence::Hdt(sentence),
+            #[cfg(feature = "gill")]
+            SentenceRef::Gilt(sentence) => Sentence::Gilt(sentence),
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svdy(sentence) => Sentence::Svdy(sentence),
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svss(sentence) => Sentence::Svss(sentence),
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svsd(sentence) => Sentence::Svsd(sentence),
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svst(sentence) => Sentence::Svst(sentence),
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svsi(sentence) => Sentence::Svsi(sentence),
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svsv(sentence) => Sentence::Svsv(sentence),
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svsh(sentence) => Sentence::Svsh(sentence),
+            #[cfg(feature = "seaview")]
+            SentenceRef::Svsw(sentence) => Sentence::Svsw(sentence),
+            SentenceRef::Query(sentence) => Sentence::Query(sentence),
+            #[cfg(feature = "alloc")]
+            SentenceRef::Custom(sentence) => Sentence::Custom(sentence),
+        }
+    }
+}
+
+/// Parses NMEA0183 sentences.
+///
+/// [`NmeaParser::parse`] and [`NmeaParser::to_nmea`] use the default [`ParserConfig`], create a
+/// parser with [`NmeaParser::with_config`] to change how strict parsing is, and add decoders
+/// for other sentences with [`NmeaParser::with_registry`].
+#[derive(Debug, Clone, Default)]
+pub struct NmeaParser {
+    config: ParserConfig,
+    #[cfg(feature = "alloc")]
+    registry: SentenceRegistry,
+}
+
+impl NmeaParser {
+    pub fn with_config(config: ParserConfig) -> Self {
+        NmeaParser {
+            config,
+            #[cfg(feature = "alloc")]
+            registry: SentenceRegistry::default(),
+        }
+    }
+
+    /// Decodes sentences with the decoders in `registry` before falling back to the ones built
+    /// into this crate.
+    #[cfg(feature = "alloc")]
+    pub fn with_registry(self, registry: SentenceRegistry) -> Self {
+        NmeaParser { registry, ..self }
+    }
+
+    pub fn config(&self) -> &ParserConfig {
+        &self.config
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn registry(&self) -> &SentenceRegistry {
+        &self.registry
+    }
+
+    pub fn parse(nmea_sentence: &str) -> Result<Sentence, ParseNMEA0183Error> {
+        NmeaParser::default().parse_line(nmea_sentence)
+    }
+
+    /// Parses a sentence without copying the parts of it that are kept, the returned
+    /// [`SentenceRef`] borrows from `nmea_sentence`.
+    pub fn parse_ref(nmea_sentence: &str) -> Result<SentenceRef<'_>, ParseNMEA0183Error> {
+        NmeaParser::default().parse_line_ref(nmea_sentence)
+    }
+
+    /// Splits a sentence into its talker ID, message ID and fields and verifies the checksum,
+    /// without decoding it into a [`Sentence`].  The returned [`Nmea`] borrows from `nmea_sentence`.
+    pub fn to_nmea(nmea_sentence: &str) -> Result<Nmea<'_>, ParseNMEA0183Error> {
+        NmeaParser::default().split_line(nmea_sentence)
+    }
+
+    /// Parses a sentence, decoding fields with invalid values as `None` instead of rejecting
+    /// the sentence.  Each such field is returned as a warning.
+    pub fn parse_lenient(
+        nmea_sentence: &str,
+    ) -> Result<(Sentence, List<FieldError>), ParseNMEA0183Error> {
+        NmeaParser::default().parse_line_lenient(nmea_sentence)
+    }
+
+    /// Parses and decodes a sentence according to the parser's configuration.
+    pub fn parse_line(&self, nmea_sentence: &str) -> Result<Sentence, ParseNMEA0183Error> {
+        self.parse_line_ref(nmea_sentence)
+            .map(SentenceRef::into_owned)
+    }
+
+    /// Like [`NmeaParser::parse_ref`], but according to the parser's configuration.
+    pub fn parse_line_ref<'a>(
+        &self,
+        nmea_sentence: &'a str,
+    ) -> Result<SentenceRef<'a>, ParseNMEA0183Error> {
+        self.decode_line(nmea_sentence, &mut DecodeContext::new(DecodeMode::Strict))
+    }
+
+    /// Like [`NmeaParser::parse_lenient`], but according to the parser's configuration.
+    pub fn parse_line_lenient(
+        &self,
+        nmea_sentence: &str,
+    ) -> Result<(Sentence, List<FieldError>), ParseNMEA0183Error> {
+        let mut ctx = DecodeContext::new(DecodeMode::Lenient);
+        let sentence = self.decode_line(nmea_sentence, &mut ctx)?.into_owned();
+        let warnings = ctx
+            .into_warnings()
+            .into_iter()
+            .map(|mut warning| {
+                warning.line = Some(nmea_sentence.trim().to_string());
+                warning
+            })
+            .collect();
+        Ok((sentence, warnings))
+    }
+
+    fn decode_line<'a>(
+        &self,
+        nmea_sentence: &'a str,
+        ctx: &mut DecodeContext,
+    ) -> Result<SentenceRef<'a>, ParseNMEA0183Error> {
+        let nmea_sentence = nmea_sentence.trim();
+        let nmea = self.split_line(nmea_sentence)?;
+        #[cfg(feature = "alloc")]
+        let sentence = self.registry.decode(nmea, ctx);
+        #[cfg(not(feature = "alloc"))]
+        let sentence = SentenceRef::decode(nmea, ctx);
+        match sentence.map_err(|e| e.with_line(nmea_sentence))? {
+            SentenceRef::Unknown { nmea, .. }
+                if self.config.unknown_sentences == UnknownSentencePolicy::Error =>
+            {
+                Err(ParseNMEA0183Error::UnknownSentence(format!(
+                    "{}{}",
+                    nmea.talker_id.manufacturer().unwrap_or_default(),
+                    nmea.message_id
+                )))
             }
+            SentenceRef::Unknown { nmea, .. } => Ok(SentenceRef::Unknown {
+                nmea,
+                raw: Some(Cow::Borrowed(nmea_sentence)),
+            }),
+            sentence => Ok(sentence),
         }
+    }
+
+    /// Like [`NmeaParser::to_nmea`], but according to the parser's configuration.
+    pub fn split_line<'a>(&self, nmea_sentence: &'a str) -> Result<Nmea<'a>, ParseNMEA0183Error> {
+        let scanned = scanner::scan_sentence(nmea_sentence)?;
+        let tag_block = match scanned.tag_block {
+            Some((params, checksum)) => {
+                self.verify_checksum(checksum)?;
+                Some(TagBlock::parse(params)?)
+            }
+            None => None,
+        };
+        self.verify_checksum(scanned.checksum)?;
 
-        //Return an error if the checksum is invalid
-        if checksum.is_some() {
-            NmeaParser::verify_checksum(nmea_sentence, checksum.unwrap())?;
+        if let Some(max) = self.config.max_sentence_length {
+            if scanned.body_end - scanned.body_start > max {
+                return Err(ParseNMEA0183Error::SentenceTooLong(max));
+            }
+        }
+        let talker_id = match scanned.manufacturer {
+            Some(manufacturer) => TalkerId::Proprietary {
+                manufacturer: manufacturer.parse()?,
+            },
+            None => scanned.talker_id.parse()?,
+        };
+        let message_type = scanned.message_id;
+        if !self.config.accepts_talker(&talker_id) {
+            return Err(ParseNMEA0183Error::TalkerFiltered(
+                talker_id.prefix().to_string(),
+            ));
+        }
+        if !self.config.accepts_message(&talker_id, message_type) {
+            return Err(ParseNMEA0183Error::MessageFiltered(format!(
+                "{}{}",
+                talker_id.manufacturer().unwrap_or_default(),
+                message_type
+            )));
         }
 
         Ok(Nmea {
+            tag_block,
+            delimiter: scanned.delimiter,
             talker_id,
-            message_id: message_type,
-            fields,
+            message_id: Cow::Borrowed(message_type),
+            fields: scanned.fields,
         })
     }
+
+    fn verify_checksum(&self, checksum: scanner::Checksum<'_>) -> Result<(), ParseNMEA0183Error> {
+        let expected = match (self.config.checksum, checksum.expected) {
+            (ChecksumPolicy::Ignored, _) | (ChecksumPolicy::Optional, None) => return Ok(()),
+            (ChecksumPolicy::Required, None) => return Err(ParseNMEA0183Error::MissingChecksum),
+            (_, Some(expected)) => u8::from_str_radix(expected, 16)
+                .map_err(|_| ParseNMEA0183Error::NMEA0183ChecksumError)?,
+        };
+
+        if expected != checksum.computed {
+            return Err(sentences::error::ParseNMEA0183Error::NMEA0183ChecksumError);
+        }
+
+        Ok(())
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std", feature = "all-sentences"))]
 mod tests {
     use chrono::NaiveDateTime;
     use sentences::{gga::FixQuality, pgilt::ZOrientation, TransducerReading, UnitsOfMeasurement};
@@ -208,8 +857,8 @@ mod tests {
         println!("{:?}", output);
         match output {
             Sentence::Gilt(nmea) => {
-                assert_eq!(nmea.talker_id, "P");
-                assert_eq!(nmea.message_id, "GILT");
+                assert_eq!(nmea.talker_id, "PGIL");
+                assert_eq!(nmea.message_id, "T");
                 if let Some(TransducerReading::AngularDisplacement(r)) = nmea.x_tilt {
                     assert_eq!(r.reading, Some(0.0));
                     assert_eq!(r.units, Some(UnitsOfMeasurement::Degrees));
@@ -239,13 +888,13 @@ mod tests {
             Sentence::Svsd(nmea) => {
                 let eps = 1e-6;
 
-                assert_eq!(nmea.talker_id, "P");
-                assert_eq!(nmea.message_id, "SVSD");
+                assert_eq!(nmea.talker_id, "PSVS");
+                assert_eq!(nmea.message_id, "D");
 
                 // There should be 14 bins
-                assert_eq!(nmea.directional_spectrum_bins.len(), 14);
+                assert_eq!(nmea.esmag.len(), 14);
 
-                for e in nmea.directional_spectrum_bins.iter() {
+                for e in nmea.esmag.iter() {
                     assert_abs_diff_eq!(*e, 0.0f32, epsilon = eps);
                 }
             }
@@ -260,8 +909,8 @@ mod tests {
 
         match output {
             Sentence::Svsv(nmea) => {
-                assert_eq!(nmea.talker_id, "P");
-                assert_eq!(nmea.message_id, "SVSV");
+                assert_eq!(nmea.talker_id, "PSVS");
+                assert_eq!(nmea.message_id, "V");
 
                 // Check numeric fields
                 assert_eq!(nmea.time.unwrap().get::<second>(), 0.000);
@@ -280,8 +929,8 @@ mod tests {
 
         match output {
             Sentence::Svsi(nmea) => {
-                assert_eq!(nmea.talker_id, "P");
-                assert_eq!(nmea.message_id, "SVSI");
+                assert_eq!(nmea.talker_id, "PSVS");
+                assert_eq!(nmea.message_id, "I");
 
                 let expected = "WINDMILL";
                 assert_eq!(nmea.identity, expected);
@@ -297,8 +946,8 @@ mod tests {
 
         match output {
             Sentence::Svst(nmea) => {
-                assert_eq!(nmea.talker_id, "P");
-                assert_eq!(nmea.message_id, "SVST");
+                assert_eq!(nmea.talker_id, "PSVS");
+                assert_eq!(nmea.message_id, "T");
 
                 let expected =
                     NaiveDateTime::parse_from_str("2020-10-02 16:04:53", "%Y-%m-%d %H:%M:%S")
@@ -320,8 +969,8 @@ mod tests {
             Sentence::Svss(nmea) => {
                 let eps = 1e-6;
 
-                assert_eq!(nmea.talker_id, "P");
-                assert_eq!(nmea.message_id, "SVSS");
+                assert_eq!(nmea.talker_id, "PSVS");
+                assert_eq!(nmea.message_id, "S");
 
                 let bin = &nmea.bin;
 
@@ -355,8 +1004,8 @@ mod tests {
             Sentence::Svdy(nmea) => {
                 let eps = 1e-4; // epsilon for small float comparison
 
-                assert_eq!(nmea.talker_id, "P");
-                assert_eq!(nmea.message_id, "SVDY");
+                assert_eq!(nmea.talker_id, "PSVD");
+                assert_eq!(nmea.message_id, "Y");
 
                 assert_abs_diff_eq!(nmea.acc_x.unwrap().value, -0.210, epsilon = eps);
                 assert_abs_diff_eq!(nmea.acc_y.unwrap().value, -0.116, epsilon = eps);
@@ -380,6 +1029,613 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_to_nmea_borrows_fields() {
+        let input = "$WIMWV,049,R,000.03,N,A*03";
+        let nmea = NmeaParser::to_nmea(input).unwrap();
+        assert_eq!(nmea.talker_id, TalkerId::WeatherInstrument);
+        assert!(matches!(nmea.message_id, Cow::Borrowed("MWV")));
+        assert!(nmea.fields.iter().all(|f| matches!(f, Cow::Borrowed(_))));
+        assert_eq!(nmea.fields, ["049", "R", "000.03", "N", "A"]);
+
+        let owned: Nmea<'static> = nmea.clone().into_owned();
+        assert_eq!(owned, nmea);
+        assert!(matches!(Sentence::try_from(owned), Ok(Sentence::Mwv(_))));
+    }
+
+    #[test]
+    fn test_proprietary_talker_id() {
+        let nmea = NmeaParser::to_nmea("$PGILT,A,+00,D,+01,D,+1,TILT*35").unwrap();
+        assert_eq!(
+            nmea.talker_id,
+            TalkerId::Proprietary {
+                manufacturer: "GIL".parse().unwrap()
+            }
+        );
+        assert_eq!(nmea.message_id, "T");
+        assert!(matches!(Sentence::try_from(nmea), Ok(Sentence::Gilt(_))));
+
+        // A manufacturer may use its mnemonic alone as the address.
+        let nmea = NmeaParser::to_nmea("$PGRM,1").unwrap();
+        assert_eq!(nmea.talker_id.manufacturer(), Some("GRM"));
+        assert_eq!(nmea.message_id, "");
+        assert!(matches!(
+            Sentence::try_from(nmea),
+            Ok(Sentence::Unknown { .. })
+        ));
+
+        // The same message ID from another talker is not the proprietary sentence.
+        assert!(matches!(
+            NmeaParser::parse("$IIGILT,A,-0.20,D,0.09,D,1,Test"),
+            Ok(Sentence::Unknown { .. })
+        ));
+    }
+
+    #[test]
+    fn test_encapsulation() {
+        let input = "!AIVDM,1,1,,A,13aEOK?P00PD2wVMdLDRhgvL289?,0*26";
+        let nmea = NmeaParser::to_nmea(input).unwrap();
+        assert_eq!(nmea.delimiter, Delimiter::Encapsulation);
+        assert_eq!(nmea.talker_id, "AI");
+        assert_eq!(nmea.message_id, "VDM");
+        assert_eq!(nmea.fields[4], "13aEOK?P00PD2wVMdLDRhgvL289?");
+        assert!(matches!(
+            NmeaParser::parse(input),
+            Ok(Sentence::Unknown { .. })
+        ));
+
+        let input = "!AIVDO,1,1,,,B5NJ;PP005l4ot5Isbl03wsUkP06,0*35";
+        let nmea = NmeaParser::to_nmea(input).unwrap();
+        assert_eq!(nmea.delimiter, Delimiter::Encapsulation);
+
+        let nmea = NmeaParser::to_nmea("$WIMWV,049,R,000.03,N,A*03").unwrap();
+        assert_eq!(nmea.delimiter, Delimiter::Parametric);
+
+        let input = "!AIVDM,1,1,,A,13aEOK?P00PD2wVMdLDRhgvL289?,0*27";
+        assert!(matches!(
+            NmeaParser::parse(input),
+            Err(ParseNMEA0183Error::NMEA0183ChecksumError)
+        ));
+
+        assert!(matches!(
+            NmeaParser::parse("$HEHDT,238.5,T* 2 8"),
+            Err(ParseNMEA0183Error::NMEA0183ChecksumError)
+        ));
+    }
+
+    #[test]
+    fn test_tag_block() {
+        let input = "\\s:GP01,c:1700000000*2C\\$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56";
+        let output = NmeaParser::parse(input).unwrap();
+        match output {
+            Sentence::Gga(nmea) => {
+                let tag_block = nmea.tag_block.unwrap();
+                assert_eq!(tag_block.source, Some("GP01".to_string()));
+                assert_eq!(tag_block.unix_time, Some(1700000000));
+                assert_eq!(nmea.fix_time, Some(113342.000));
+            }
+            _ => panic!("Expected Gga"),
+        }
+
+        let input = "\\s:GP01,c:1700000000*2D\\$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56";
+        assert!(matches!(
+            NmeaParser::parse(input),
+            Err(ParseNMEA0183Error::NMEA0183ChecksumError)
+        ));
+
+        let input = "\\s:GP01,x:foo*57\\$HEHDT,238.5,T*23";
+        match NmeaParser::parse(input) {
+            Ok(Sentence::Hdt(hdt)) => {
+                let tag_block = hdt.tag_block.unwrap();
+                assert_eq!(tag_block.source, Some("GP01".to_string()));
+                assert_eq!(tag_block.other().len(), 1);
+            }
+            output => panic!("Expected Hdt, got {:?}", output),
+        }
+
+        let output = NmeaParser::parse("$WIMWV,049,R,000.03,N,A*03").unwrap();
+        match output {
+            Sentence::Mwv(nmea) => assert!(nmea.tag_block.is_none()),
+            _ => panic!("Expected Mwv"),
+        }
+    }
+
+    #[test]
+    fn test_write_nmea() {
+        let input = "\\c:1700000000,s:GP01*2C\\$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56";
+        let nmea = NmeaParser::to_nmea(input).unwrap();
+        assert_eq!(nmea.to_string(), input);
+
+        let input = "!AIVDM,1,1,,A,13aEOK?P00PD2wVMdLDRhgvL289?,0*26";
+        let nmea = NmeaParser::to_nmea(input).unwrap();
+        assert_eq!(nmea.to_string(), input);
+    }
+
+    #[test]
+    fn test_escaped_fields() {
+        let input = "$PSVSI,WIND^2CMILL^7E2*42";
+        match NmeaParser::parse(input) {
+            Ok(Sentence::Svsi(nmea)) => assert_eq!(nmea.identity, "WIND,MILL~2"),
+            _ => panic!("Expected Svsi"),
+        }
+
+        let input = "$GPTXT,01,01,02,ALARM^21 ^5EHIGH^5E TEMP*41";
+        let nmea = NmeaParser::to_nmea(input).unwrap();
+        assert_eq!(nmea.fields.len(), 4);
+        assert_eq!(nmea.fields[3], "ALARM! ^HIGH^ TEMP");
+        assert!(matches!(nmea.fields[0], Cow::Borrowed("01")));
+        assert_eq!(nmea.to_string(), input);
+
+        // A literal `~` is accepted, but escaped when the sentence is written.
+        let nmea = NmeaParser::to_nmea("$IIALR,,,,~ok*25").unwrap();
+        assert_eq!(nmea.fields[3], "~ok");
+        assert_eq!(nmea.to_string(), "$IIALR,,,,^7Eok*77");
+
+        let mut nmea = nmea.into_owned();
+        nmea.fields[3] = Cow::Borrowed("a,b*c");
+        assert_eq!(
+            NmeaParser::to_nmea(&nmea.to_string()).unwrap().fields[3],
+            "a,b*c"
+        );
+
+        // Like the grammar, the sentence ends before a broken escape.
+        for (input, fields) in [
+            ("$GPTXT,01,01,02,A^2", 4),
+            ("$GPTXT,01,01,02,A^ZZ", 4),
+            ("$GPTXT,^", 1),
+        ] {
+            let nmea = NmeaParser::to_nmea(input).unwrap();
+            assert_eq!(nmea.fields.len(), fields, "{}", input);
+            assert!(!nmea.fields[fields - 1].contains('^'), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_trailing_garbage() {
+        // The grammar is not anchored to the end of the line, text after a sentence is ignored.
+        let expected = NmeaParser::parse("$WIMWV,049,R,000.03,N,A*03").unwrap();
+        let input = "$WIMWV,049,R,000.03,N,A*03\x00garbage";
+        assert_eq!(NmeaParser::parse(input).unwrap(), expected);
+
+        // Without a checksum the sentence ends at the first character that is not allowed.
+        let input = "$WIMWV,049,R,000.03,N,A\x00*03";
+        assert_eq!(NmeaParser::parse(input).unwrap(), expected);
+        let parser = NmeaParser::with_config(ParserConfig::strict());
+        assert!(matches!(
+            parser.parse_line(input),
+            Err(ParseNMEA0183Error::MissingChecksum)
+        ));
+    }
+
+    #[test]
+    fn test_spaces_between_tokens() {
+        let expected = NmeaParser::parse("$WIMWV,049,R,000.03,N,A*03").unwrap();
+        // The checksum covers the spaces as well.
+        for input in [
+            "$ WIMWV,049,R,000.03,N,A*23",
+            "$WIMWV ,049,R,000.03,N,A*23",
+            "$WI MWV,049,R,000.03,N,A*23",
+            "$WIMWV, 049,R,000.03,N,A*23",
+        ] {
+            assert_eq!(NmeaParser::parse(input).unwrap(), expected, "{}", input);
+        }
+
+        // Spaces the grammar would skip are written as escapes.
+        let nmea = NmeaParser::to_nmea("$GPTXT,^20a ,b, c^20").unwrap();
+        assert_eq!(nmea.fields, [" a ", "b", " c "]);
+        assert_eq!(nmea.to_string(), "$GPTXT,^20a ,b, c^20*03");
+        assert_eq!(
+            NmeaParser::to_nmea("$GPTXT, a ,b, c ").unwrap().fields,
+            ["a ", "b", " c"]
+        );
+    }
+
+    #[test]
+    fn test_checksum_policy() {
+        let without = "$WIMWV,049,R,000.03,N,A";
+        let wrong = "$WIMWV,049,R,000.03,N,A*00";
+        let tagged = "\\s:GP01,c:1700000000*00\\$WIMWV,049,R,000.03,N,A*03";
+
+        let parser = NmeaParser::with_config(ParserConfig {
+            checksum: ChecksumPolicy::Required,
+            ..Default::default()
+        });
+        assert!(matches!(
+            parser.parse_line(without),
+            Err(ParseNMEA0183Error::MissingChecksum)
+        ));
+        assert!(matches!(
+            parser.parse_line(wrong),
+            Err(ParseNMEA0183Error::NMEA0183ChecksumError)
+        ));
+
+        let parser = NmeaParser::default();
+        assert!(parser.parse_line(without).is_ok());
+        assert!(parser.parse_line(wrong).is_err());
+        assert!(parser.parse_line(tagged).is_err());
+
+        let parser = NmeaParser::with_config(ParserConfig::lenient());
+        assert!(parser.parse_line(without).is_ok());
+        assert!(parser.parse_line(wrong).is_ok());
+        assert!(parser.parse_line(tagged).is_ok());
+    }
+
+    #[test]
+    fn test_max_sentence_length() {
+        let input = "\\s:GP01,c:1700000000*2C\\$WIMWV,049,R,000.03,N,A*03";
+        let parser = NmeaParser::with_config(ParserConfig {
+            max_sentence_length: Some(26),
+            ..Default::default()
+        });
+        assert!(parser.parse_line(input).is_ok());
+
+        let parser = NmeaParser::with_config(ParserConfig {
+            max_sentence_length: Some(25),
+            ..Default::default()
+        });
+        assert!(matches!(
+            parser.parse_line(input),
+            Err(ParseNMEA0183Error::SentenceTooLong(25))
+        ));
+
+        let input = "$PSVSS,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000*55";
+        let parser = NmeaParser::with_config(ParserConfig::strict());
+        assert!(parser.parse_line(input).is_err());
+        assert!(NmeaParser::parse(input).is_ok());
+    }
+
+    #[test]
+    fn test_talker_and_message_filters() {
+        let parser = NmeaParser::with_config(ParserConfig {
+            allowed_talkers: Some(vec!["WI".to_string(), "GP".to_string()]),
+            denied_messages: vec!["GGA".to_string()],
+            ..Default::default()
+        });
+        assert!(parser.parse_line("$WIMWV,049,R,000.03,N,A*03").is_ok());
+        assert!(matches!(
+            parser.parse_line("$PSVSI,WINDMILL*73"),
+            Err(ParseNMEA0183Error::TalkerFiltered(t)) if t == "P"
+        ));
+        assert!(matches!(
+            parser.parse_line(
+                "$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56"
+            ),
+            Err(ParseNMEA0183Error::MessageFiltered(m)) if m == "GGA"
+        ));
+
+        let parser = NmeaParser::with_config(ParserConfig {
+            denied_talkers: vec!["WI".to_string()],
+            allowed_messages: Some(vec!["SVSI".to_string()]),
+            ..Default::default()
+        });
+        assert!(parser.parse_line("$WIMWV,049,R,000.03,N,A*03").is_err());
+        assert!(parser.parse_line("$PSVSI,WINDMILL*73").is_ok());
+    }
+
+    #[test]
+    fn test_unknown_sentence_policy() {
+        let input = "$GPGSV,2,2,08,15,30,050,47,19,09,158,,26,12,281,40,27,38,173,41*7B";
+        assert!(matches!(
+            NmeaParser::parse(input),
+            Ok(Sentence::Unknown { .. })
+        ));
+
+        let parser = NmeaParser::with_config(ParserConfig {
+            unknown_sentences: UnknownSentencePolicy::Error,
+            ..Default::default()
+        });
+        assert!(matches!(
+            parser.parse_line(input),
+            Err(ParseNMEA0183Error::UnknownSentence(m)) if m == "GSV"
+        ));
+    }
+
+    #[test]
+    fn test_parse_ref_borrows_from_the_line() {
+        let input = "$GPGSV,2,2,08,15,30,050,47*40";
+        let sentence = NmeaParser::parse_ref(input).unwrap();
+        match &sentence {
+            SentenceRef::Unknown { nmea, raw } => {
+                assert!(matches!(nmea.message_id, Cow::Borrowed("GSV")));
+                assert!(nmea.fields.iter().all(|f| matches!(f, Cow::Borrowed(_))));
+                assert!(matches!(raw, Some(Cow::Borrowed(line)) if *line == input));
+            }
+            _ => panic!("Expected Unknown"),
+        }
+        assert_eq!(sentence.into_owned(), NmeaParser::parse(input).unwrap());
+
+        let Ok(SentenceRef::Hdt(hdt)) = NmeaParser::parse_ref("$HEHDT,240.0,T*29") else {
+            panic!("Expected HDT");
+        };
+        assert!(matches!(hdt.message_id, Cow::Borrowed("HDT")));
+    }
+
+    #[test]
+    fn test_unknown_sentence_keeps_data() {
+        let input = "\\s:r003*08\\$GPGSV,2,2,08,15,30,050,47,19,09,158,,26,12,281,40,27,38,173,41";
+        match NmeaParser::parse(&format!("{}\r\n", input)).unwrap() {
+            Sentence::Unknown { nmea, raw } => {
+                assert_eq!(nmea.talker_id, TalkerId::Gps);
+                assert_eq!(nmea.message_id, "GSV");
+                assert_eq!(nmea.fields.len(), 19);
+                assert_eq!(nmea.fields[3], "15");
+                assert_eq!(nmea.tag_block.unwrap().source.as_deref(), Some("r003"));
+                assert_eq!(raw.as_deref(), Some(input));
+            }
+            _ => panic!("Expected Unknown"),
+        }
+
+        let nmea = NmeaParser::to_nmea("$PACMS,A*21").unwrap();
+        match Sentence::try_from(nmea.clone()).unwrap() {
+            Sentence::Unknown { nmea: unknown, raw } => {
+                assert_eq!(unknown, nmea);
+                assert_eq!(unknown.to_string(), "$PACMS,A*21");
+                assert_eq!(raw, None);
+            }
+            _ => panic!("Expected Unknown"),
+        }
+    }
+
+    #[test]
+    fn test_query() {
+        match NmeaParser::parse("$CCGPQ,GGA*2B") {
+            Ok(Sentence::Query(query)) => {
+                assert_eq!(query.requester, TalkerId::Computer);
+                assert_eq!(query.target, TalkerId::Gps);
+                assert_eq!(query.message_id, "GGA");
+                assert_eq!(query.to_string(), "$CCGPQ,GGA*2B");
+            }
+            _ => panic!("Expected Query"),
+        }
+
+        let query = Query::new(
+            TalkerId::IntegratedInstrumentation,
+            TalkerId::WeatherInstrument,
+            "MWV",
+        );
+        assert_eq!(query.to_string(), "$IIWIQ,MWV*2F");
+        let nmea = NmeaParser::to_nmea("$IIWIQ,MWV*2F").unwrap();
+        assert_eq!(nmea, query.to_nmea().unwrap());
+        assert_eq!(Query::try_from(nmea).unwrap(), query);
+
+        // Neither proprietary sentences, longer message IDs nor sentences without a single
+        // three letter field are queries.
+        for line in [
+            "$CCGPQ,",
+            "$GPAAQ,1,2",
+            "$GPAAQ,GGA,GSV",
+            "$CCGPQ,GG1",
+            "$CCGPQ,gga",
+        ] {
+            assert!(
+                matches!(NmeaParser::parse(line), Ok(Sentence::Unknown { .. })),
+                "{}",
+                line
+            );
+        }
+        assert!(matches!(
+            NmeaParser::parse("$PGRMQ,GGA"),
+            Ok(Sentence::Unknown { .. })
+        ));
+        assert!(matches!(
+            NmeaParser::parse("$CCGPGQ,GGA"),
+            Ok(Sentence::Unknown { .. })
+        ));
+        assert!(matches!(
+            Query::try_from(NmeaParser::to_nmea("$GPGSV,1").unwrap()),
+            Err(ParseNMEA0183Error::UnknownSentence(m)) if m == "GSV"
+        ));
+    }
+
+    /// A valid sentence of every supported type, with the number of fields its decoder requires.
+    /// `None` marks sentences with a variable number of fields.
+    const SAMPLES: [(&str, Option<u16>); 15] = [
+        ("$WIMWV,049,R,000.03,N,A*03", Some(5)),
+        ("$WIXDR,C,+023.1,C,TEMP,P,0.9989,B,PRESS,H,040,P,RH*25", None),
+        (
+            "$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56",
+            Some(14),
+        ),
+        ("$HCHDM,238.5,M", Some(2)),
+        ("$HEHDT,238.5,T", Some(2)),
+        ("$PGILT,A,+00,D,+01,D,+1,TILT*35", Some(7)),
+        (
+            "$PSVDY,-0.210,-0.116,9.825,-0.0044,0.0011,-0.0044,217.3,0.6,-1.4,0.021,-0.012,9.828,703*6C",
+            Some(13),
+        ),
+        (
+            "$PSVSS,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000*55",
+            Some(14),
+        ),
+        ("$PSVSD,128.0000,0.0000,0.0000,0.0000", None),
+        ("$PSVST,2020-10-02 16:04:53*58", None),
+        ("$PSVSI,WINDMILL*73", None),
+        ("$PSVSV,0.000,0.024,0.037,-0.663*7C", Some(4)),
+        ("$PSVSH,Period,ESmag,Nre,Nim,Ere,Eim,Ure,Uim,Umag,theta,a1,b1,a2,b2", None),
+        ("$PSVSW,0.570,2.560,311.141,311.142,3.872,1.559,0.118,3.824,12.6,23.8", None),
+        ("$CCGPQ,GGA*2B", None),
+    ];
+
+    #[test]
+    fn test_truncated_sentences() {
+        for (input, required) in SAMPLES {
+            assert!(NmeaParser::parse(input).is_ok(), "{}", input);
+
+            let body = input.split('*').next().unwrap();
+            for (i, _) in body.match_indices(',').skip(1) {
+                let truncated = &body[..i];
+                let output = NmeaParser::parse(truncated);
+                if let Some(required) = required {
+                    assert!(
+                        matches!(output, Err(ParseNMEA0183Error::MissingFields(n)) if n == required),
+                        "{}: {:?}",
+                        truncated,
+                        output
+                    );
+                }
+            }
+        }
+
+        assert!(matches!(
+            NmeaParser::parse("$WIXDR,C,+023.1,C,TEMP,P,0.9989"),
+            Err(ParseNMEA0183Error::MissingFields(8))
+        ));
+    }
+
+    #[test]
+    fn test_field_error_context() {
+        let input = "$GPGGA,113342.000,5045.7837,N,00132.4127,W,9,06,1.3,-10.2,M,47.8,M,,0000";
+        let Err(ParseNMEA0183Error::InvalidField(e)) = NmeaParser::parse(input) else {
+            panic!("Expected InvalidField");
+        };
+        assert_eq!(e.talker_id, "GP");
+        assert_eq!(e.message_id, "GGA");
+        assert_eq!(e.index, 5);
+        assert_eq!(e.name, Some("fix_quality"));
+        assert_eq!(e.raw, "9");
+        assert_eq!(e.kind, FieldErrorKind::InvalidEnumerator);
+        assert_eq!(e.line.as_deref(), Some(input));
+
+        let input = "$PSVSS,x.0,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000,0.0000";
+        let error = NmeaParser::parse(input).unwrap_err();
+        assert_eq!(error.line(), Some(input));
+        assert_eq!(
+            error.to_string(),
+            "Failed to parse field 0 of PSVSS (period): invalid float literal in `x.0`"
+        );
+
+        let input = "$WIMWV,049,X,000.03,N,A";
+        let error = Sentence::try_from(NmeaParser::to_nmea(input).unwrap()).unwrap_err();
+        assert_eq!(error.line(), None);
+        assert_eq!(
+            error.to_string(),
+            "Failed to parse field 1 of WIMWV (wind_dir_type): field did not match any enumerators in `X`"
+        );
+    }
+
+    #[test]
+    fn test_parse_field_missing() {
+        let nmea = NmeaParser::to_nmea("$PXYZD,12.5").unwrap();
+        assert_eq!(nmea.parse_field::<f32>(0, "x").unwrap(), 12.5);
+        assert!(matches!(
+            nmea.parse_field::<f32>(3, "x"),
+            Err(ParseNMEA0183Error::MissingFields(4))
+        ));
+    }
+
+    #[test]
+    fn test_lenient_decoding() {
+        let input = "$GPGGA,113342.000,5045.7837,N,00132.4127,W,7,06,1.3,-10.2,M,47.8,M,,0000";
+        assert!(matches!(
+            NmeaParser::parse(input),
+            Err(ParseNMEA0183Error::InvalidField(_))
+        ));
+
+        let (output, warnings) = NmeaParser::parse_lenient(input).unwrap();
+        match output {
+            Sentence::Gga(nmea) => {
+                assert_eq!(nmea.fix_quality, None);
+                assert_eq!(nmea.num_satellites, Some(6));
+            }
+            _ => panic!("Expected Gga"),
+        }
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].index, 5);
+        assert_eq!(warnings[0].raw, "7");
+        assert_eq!(warnings[0].line.as_deref(), Some(input));
+
+        let input = "$WIMWV,049,X,000.03,N,Q";
+        assert!(NmeaParser::parse(input).is_err());
+        let (output, warnings) = NmeaParser::parse_lenient(input).unwrap();
+        match output {
+            Sentence::Mwv(nmea) => {
+                assert!(nmea.wind_dir_type.is_none());
+                assert!(nmea.acceptable.is_none());
+                assert_eq!(nmea.wind_speed, Some(0.03));
+            }
+            _ => panic!("Expected Mwv"),
+        }
+        let names: Vec<_> = warnings.iter().map(|w| w.name).collect();
+        assert_eq!(names, [Some("wind_dir_type"), Some("acceptable")]);
+
+        let (output, warnings) = NmeaParser::parse_lenient("$PGILT,A,+00,D,+01,D,+2,TILT").unwrap();
+        assert!(matches!(
+            output,
+            Sentence::Gilt(Gilt {
+                z_orientation: None,
+                ..
+            })
+        ));
+        assert_eq!(warnings.len(), 1);
+
+        let (_, warnings) = NmeaParser::parse_lenient("$WIMWV,049,R,000.03,N,A*03").unwrap();
+        assert!(warnings.is_empty());
+
+        // Sentences with derived decoders too.
+        for (input, name) in [
+            ("$HEHDT,238.5,X", "heading_type"),
+            ("$HEHDT,abc,T", "heading"),
+        ] {
+            assert!(matches!(
+                NmeaParser::parse(input),
+                Err(ParseNMEA0183Error::InvalidField(e)) if e.name == Some(name)
+            ));
+            let (output, warnings) = NmeaParser::parse_lenient(input).unwrap();
+            assert!(matches!(output, Sentence::Hdt(_)));
+            let names: Vec<_> = warnings.iter().map(|w| w.name).collect();
+            assert_eq!(names, [Some(name)]);
+        }
+
+        // Missing fields are still fatal
+        assert!(NmeaParser::parse_lenient("$WIMWV,049,X").is_err());
+    }
+
+    #[test]
+    fn test_lenient_decoding_reports_every_dropped_field() {
+        let input = "$PSVDY,x,-0.116,9.825,-0.0044,0.0011,-0.0044,y,0.6,-1.4,0.021,-0.012,9.828,z";
+        assert!(matches!(
+            NmeaParser::parse(input),
+            Err(ParseNMEA0183Error::InvalidField(e)) if e.index == 0
+        ));
+        let (output, warnings) = NmeaParser::parse_lenient(input).unwrap();
+        let Sentence::Svdy(svdy) = output else {
+            panic!("Expected Svdy");
+        };
+        assert_eq!((svdy.acc_x, svdy.heading, svdy.index), (None, None, None));
+        assert!(svdy.acc_y.is_some());
+        let dropped: Vec<_> = warnings.iter().map(|w| (w.index, w.name)).collect();
+        assert_eq!(
+            dropped,
+            [
+                (0, Some("acc_x")),
+                (6, Some("heading")),
+                (12, Some("index"))
+            ]
+        );
+
+        let input = "$GPGGA,113342.000,5045.7837,X,00132.4127,W,1,six,1.3,-10.2,M,47.8,M,,0000";
+        assert!(NmeaParser::parse(input).is_err());
+        let (output, warnings) = NmeaParser::parse_lenient(input).unwrap();
+        let Sentence::Gga(gga) = output else {
+            panic!("Expected Gga");
+        };
+        assert_eq!((gga.latitude_hemisphere, gga.num_satellites), (None, None));
+        let names: Vec<_> = warnings.iter().map(|w| w.name).collect();
+        assert_eq!(names, [Some("latitude_hemisphere"), Some("num_satellites")]);
+
+        let input = "$WIXDR,C,warm,C,TEMP,P,1.0243,X,PRESS";
+        assert!(NmeaParser::parse(input).is_err());
+        let (_, warnings) = NmeaParser::parse_lenient(input).unwrap();
+        let dropped: Vec<_> = warnings.iter().map(|w| (w.index, w.name)).collect();
+        assert_eq!(
+            dropped,
+            [(1, Some("readings.reading")), (6, Some("readings.units"))]
+        );
+
+        let (_, warnings) = NmeaParser::parse_lenient("$PSVST,yesterday").unwrap();
+        assert_eq!(warnings[0].kind, FieldErrorKind::InvalidFormat);
+    }
+
     #[test]
     fn test_checksum() {
         let input = "$WIMWV,049,R,000.03,N,A*03";
@@ -398,6 +1654,193 @@ mod tests {
         let _ = NmeaParser::parse(input).unwrap();
     }
 
+    #[test]
+    fn test_nmea_sentence() {
+        for (input, _) in SAMPLES {
+            let sentence = NmeaParser::parse(input).unwrap();
+            let nmea = NmeaParser::to_nmea(input).unwrap();
+            assert_eq!(sentence.talker_id(), &nmea.talker_id, "{}", input);
+            assert_eq!(sentence.message_id(), nmea.message_id, "{}", input);
+            assert!(sentence.timestamp().is_none() || input.starts_with("$PSVST"));
+
+            // Encoding keeps the type of the sentence and its number of fields.
+            let encoded = sentence.to_nmea().unwrap().to_string();
+            let decoded = NmeaParser::parse(&encoded).unwrap();
+            assert_eq!(
+                std::mem::discriminant(&decoded),
+                std::mem::discriminant(&sentence),
+                "{}",
+                encoded
+            );
+            let fields = NmeaParser::to_nmea(&encoded).unwrap().fields.len();
+            assert_eq!(fields, nmea.fields.len(), "{}", encoded);
+        }
+
+        let mwv = NmeaParser::parse("$WIMWV,049,R,000.03,N,A*03").unwrap();
+        assert!(mwv
+            .to_nmea()
+            .unwrap()
+            .to_string()
+            .starts_with("$WIMWV,49,R,0.03,N,A*"));
+
+        let query = NmeaParser::parse("$CCGPQ,GGA*2B").unwrap();
+        assert_eq!(query.talker_id(), &TalkerId::Computer);
+        assert_eq!(query.message_id(), "GPQ");
+        assert_eq!(query.to_nmea().unwrap().to_string(), "$CCGPQ,GGA*2B");
+
+        let unknown = "$GPGSV,2,2,08,15,30,050,47,19,09,158,,26,12,281,40,27,38,173,41*7B";
+        assert_eq!(
+            NmeaParser::parse(unknown)
+                .unwrap()
+                .to_nmea()
+                .unwrap()
+                .to_string(),
+            unknown
+        );
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let inputs = SAMPLES.iter().map(|(input, _)| *input).chain([
+            "\\c:1700000000,s:GP01*2C\\$GNGGA,013342.25,0512.5,S,00032.4127,E,4,12,0.9,1.5,M,-2,M,1.2,31",
+            "$GPGGA,,,,,,0,,,,,,,,",
+            "$WIXDR,A,-2.5,D,PITCH,,,,",
+            "$PSVSI,WIND^2CMILL^7E2*42",
+            "$GPGSV,2,2,08,15,30,050,47,19,09,158,,26,12,281,40,27,38,173,41*7B",
+            "$WIXDR,G,12.0,,GEN,C,+023.1,C,TEMP",
+        ]);
+        for input in inputs {
+            let sentence = NmeaParser::parse(input).unwrap();
+            let encoded = sentence
+                .encode(&EncodeOptions::default())
+                .unwrap()
+                .to_string();
+            let line = encoded.strip_suffix("\r\n").unwrap();
+            assert_eq!(NmeaParser::parse(line).unwrap(), sentence, "{}", encoded);
+
+            let violations = conformance::validate(&encoded, conformance::Profile::V4);
+            let body = &line[line.find(['$', '!']).unwrap()..];
+            let long = body.len() > config::NMEA0183_MAX_SENTENCE_LENGTH;
+            assert_eq!(
+                violations.len(),
+                usize::from(long),
+                "{}: {:?}",
+                encoded,
+                violations
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_options() {
+        let gga = NmeaParser::parse(
+            "$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56",
+        )
+        .unwrap();
+        assert_eq!(
+            gga.encode(&EncodeOptions::default()).unwrap().to_string(),
+            "$GPGGA,113342,5045.7837,N,00132.4127,W,1,6,1.3,-10.2,M,47.8,M,,0*48\r\n"
+        );
+
+        let options = EncodeOptions {
+            precision: Some(2),
+            crlf: false,
+        };
+        assert_eq!(
+            gga.encode(&options).unwrap().to_string(),
+            "$GPGGA,113342,5045.78,N,00132.41,W,1,6,1.30,-10.20,M,47.80,M,,0*79"
+        );
+        let options = EncodeOptions {
+            precision: Some(0),
+            ..options
+        };
+        assert_eq!(
+            gga.encode(&options).unwrap().to_string(),
+            "$GPGGA,113342,5046,N,00132,W,1,6,1,-10,M,48,M,,0*58"
+        );
+
+        // The time is never rounded, minutes that round up to 60 are carried into the degrees.
+        let gga = NmeaParser::parse(
+            "$GPGGA,235959.600,5059.7,N,00159.97,W,1,06,1.3,-10.2,M,47.8,M,,0000",
+        )
+        .unwrap();
+        let line = gga.encode(&options).unwrap().to_string();
+        assert_eq!(
+            line,
+            "$GPGGA,235959.6,5100,N,00200,W,1,6,1,-10,M,48,M,,0*46"
+        );
+        match NmeaParser::parse(&line).unwrap() {
+            Sentence::Gga(gga) => {
+                assert_eq!(gga.fix_time, Some(235959.6));
+                assert_eq!(gga.latitude, Some(5100.0));
+                assert_eq!(gga.longitude, Some(200.0));
+            }
+            other => panic!("Expected GGA, got {:?}", other),
+        }
+
+        // Only the numbers of sentences in the catalogue are rounded.
+        let unknown = "$GPGSV,2,2,08,15,30,050,47,19,09,158,,26,12,281,40,27,38,173,41*7B";
+        let sentence = NmeaParser::parse(unknown).unwrap();
+        assert_eq!(sentence.encode(&options).unwrap().to_string(), unknown);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        use approx::assert_abs_diff_eq;
+        let inputs = SAMPLES.iter().map(|(input, _)| *input).chain([
+            "\\c:1700000000,s:GP01*2C\\$GNGGA,013342.25,0512.5,S,00032.4127,E,4,12,0.9,1.5,M,-2,M,1.2,31",
+            "$GPGSV,2,2,08,15,30,050,47,19,09,158,,26,12,281,40,27,38,173,41*7B",
+            "$WIXDR,G,12.0,,GEN,C,+023.1,C,TEMP",
+        ]);
+        for input in inputs {
+            let sentence = NmeaParser::parse(input).unwrap();
+            let json = serde_json::to_string(&sentence).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Sentence>(&json).unwrap(),
+                sentence,
+                "{}",
+                json
+            );
+        }
+
+        let json = |input| serde_json::to_value(NmeaParser::parse(input).unwrap()).unwrap();
+        let xdr = json(SAMPLES[1].0);
+        assert_eq!(
+            xdr["readings"][0].to_string(),
+            r#"{"name":"TEMP","reading":23.100000381469727,"type":"C","units":"C"}"#
+        );
+        let xdr = json("$WIXDR,G,12.0,,GEN");
+        assert_eq!(
+            xdr["readings"][0].to_string(),
+            r#"{"name":"GEN","reading":12.0,"type":"G","units":null}"#
+        );
+        let gga = json(SAMPLES[2].0);
+        assert_eq!(gga["latitude_hemisphere"], "N");
+        assert_eq!(gga["fix_quality"], "1");
+        assert_eq!(json(SAMPLES[5].0)["z_orientation"], "+1");
+        assert_eq!(json(SAMPLES[5].0)["talker_id"], "PGIL");
+        assert_eq!(json(SAMPLES[9].0)["timestamp"], "2020-10-02T16:04:53");
+        let svdy = json(SAMPLES[6].0);
+        assert_abs_diff_eq!(svdy["heading"].as_f64().unwrap(), 217.3, epsilon = 1e-4);
+        assert_eq!(svdy["acc_x"].as_f64().unwrap(), -0.21_f32 as f64);
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let gga = NmeaParser::parse(
+            "\\c:1700000000,s:GP01*2C\\$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56",
+        )
+        .unwrap();
+        let expected = NaiveDateTime::parse_from_str("2023-11-14 22:13:20", "%Y-%m-%d %H:%M:%S");
+        assert_eq!(gga.timestamp(), expected.ok());
+
+        // Sentences with a time of their own prefer it over the TAG block.
+        let svst = NmeaParser::parse("\\c:1700000000,s:GP01*2C\\$PSVST,2020-10-02 16:04:53*58");
+        let expected = NaiveDateTime::parse_from_str("2020-10-02 16:04:53", "%Y-%m-%d %H:%M:%S");
+        assert_eq!(svst.unwrap().timestamp(), expected.ok());
+    }
+
     #[test]
     #[should_panic]
     fn test_checksum_fail() {
@@ -405,3 +1848,24 @@ mod tests {
         let _ = NmeaParser::parse(input).unwrap();
     }
 }
+
+/// Decoding with only the `gnss` family of sentences enabled.
+#[cfg(all(test, feature = "std", feature = "gnss", not(feature = "wind")))]
+mod family_tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_family_is_unknown() {
+        let gga = "$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56";
+        assert!(matches!(NmeaParser::parse(gga), Ok(Sentence::Gga(_))));
+
+        let mwv = "$WIMWV,049,R,000.03,N,A*03";
+        match NmeaParser::parse(mwv) {
+            Ok(Sentence::Unknown { nmea, raw }) => {
+                assert_eq!(nmea.message_id, "MWV");
+                assert_eq!(raw.as_deref(), Some(mwv));
+            }
+            other => panic!("Expected Unknown, got {:?}", other),
+        }
+    }
+}
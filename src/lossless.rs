@@ -0,0 +1,258 @@
+//! Decoded sentences that encode back to the text they were parsed from.
+//!
+//! Decoding a field into a number loses how it was written: `+023.1` becomes `23.1`, `000.03`
+//! becomes `0.03` and `0000` becomes `0`.  A [`Lossless`] sentence, parsed with
+//! [`NmeaParser::parse_lossless`], keeps the line next to the decoded [`Sentence`].  Fields whose
+//! value was not changed are encoded with their original text, so an unchanged sentence is
+//! written byte for byte as it was received.  Changed numbers keep the sign, zero padding and
+//! decimals of the original field if they can be written that way without losing precision.
+//!
+//! ```
+//! # #[cfg(feature = "wind")] {
+//! use oor_nmea0183::{NmeaParser, Sentence};
+//!
+//! let mut mwv = NmeaParser::parse_lossless("$WIMWV,049,R,000.03,N,A").unwrap();
+//! assert_eq!(mwv.to_string(), "$WIMWV,049,R,000.03,N,A");
+//!
+//! if let Sentence::Mwv(mwv) = &mut mwv.sentence {
+//!     mwv.wind_speed = Some(1.5);
+//! }
+//! assert_eq!(mwv.to_string(), "$WIMWV,049,R,001.50,N,A*04");
+//! # }
+//! ```
+
+use crate::{
+    sentences::{error::ParseNMEA0183Error, NmeaSentence},
+    storage::{format, Cow, String, ToString},
+    tag_block::TagBlock,
+    talker::TalkerId,
+    Nmea, NmeaParser, Sentence,
+};
+use core::fmt;
+
+/// A decoded sentence together with the line it was parsed from.
+///
+/// [`fmt::Display`] writes the line itself while the sentence is unchanged, and the sentence
+/// from [`NmeaSentence::to_nmea`] with a new checksum once a value was changed.  The TAG block
+/// is written as it was received unless it was changed as well.
+#[derive(Debug, PartialEq)]
+pub struct Lossless {
+    /// The decoded sentence, its values can be changed before encoding it again.
+    pub sentence: Sentence,
+    line: String,
+    /// The fields as they were parsed.
+    raw: Nmea<'static>,
+    /// The fields as the decoded sentence encodes them, to tell which values were changed.
+    decoded: Nmea<'static>,
+}
+
+impl Lossless {
+    pub(crate) fn new(
+        sentence: Sentence,
+        raw: Nmea<'_>,
+        line: &str,
+    ) -> Result<Self, ParseNMEA0183Error> {
+        Ok(Lossless {
+            decoded: sentence.to_nmea()?,
+            sentence,
+            line: line.to_string(),
+            raw: raw.into_owned(),
+        })
+    }
+
+    /// The line the sentence was parsed from.
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// Whether a value of the sentence was changed since it was parsed.
+    pub fn is_modified(&self) -> bool {
+        self.sentence.to_nmea().ok().as_ref() != Some(&self.decoded)
+    }
+
+    /// The decoded sentence, without the text it was parsed from.
+    pub fn into_inner(self) -> Sentence {
+        self.sentence
+    }
+
+    /// The TAG block of the line, delimiters included, if it still holds the TAG block of the
+    /// sentence.
+    fn raw_tag_block(&self) -> Option<&str> {
+        if self.sentence.tag_block() != self.raw.tag_block.as_ref() {
+            return None;
+        }
+        let end = self.line.strip_prefix('\\')?.find('\\')? + 2;
+        Some(&self.line[..end])
+    }
+}
+
+impl NmeaParser {
+    /// Parses a sentence that keeps the text of its fields, see [`crate::lossless`].
+    pub fn parse_lossless(nmea_sentence: &str) -> Result<Lossless, ParseNMEA0183Error> {
+        NmeaParser::default().parse_line_lossless(nmea_sentence)
+    }
+
+    /// Like [`NmeaParser::parse_lossless`], but according to the parser's configuration.
+    pub fn parse_line_lossless(&self, nmea_sentence: &str) -> Result<Lossless, ParseNMEA0183Error> {
+        let line = nmea_sentence.trim();
+        let sentence = self.parse_line(line)?;
+        let raw = self.split_line(line)?;
+        Lossless::new(sentence, raw, line)
+    }
+}
+
+/// `value` written with the sign, zero padding and number of decimals of `raw`, if that does not
+/// change the value.
+fn like(raw: &str, value: &str) -> Option<String> {
+    raw.parse::<f64>().ok()?;
+    let value = value.parse::<f64>().ok()?;
+    let (whole, precision) = match raw.split_once('.') {
+        Some((whole, fraction)) => (whole.len(), fraction.len()),
+        None => (raw.len(), 0),
+    };
+    let width = whole + if precision > 0 { precision + 1 } else { 0 };
+    let formatted = if raw.starts_with('+') {
+        format!("{:+0width$.precision$}", value)
+    } else {
+        format!("{:0width$.precision$}", value)
+    };
+    (formatted.parse::<f64>() == Ok(value)).then_some(formatted)
+}
+
+impl NmeaSentence for Lossless {
+    fn talker_id(&self) -> &TalkerId {
+        self.sentence.talker_id()
+    }
+
+    fn message_id(&self) -> Cow<'_, str> {
+        self.sentence.message_id()
+    }
+
+    fn tag_block(&self) -> Option<&TagBlock> {
+        self.sentence.tag_block()
+    }
+
+    #[cfg(feature = "chrono")]
+    fn timestamp(&self) -> Option<chrono::NaiveDateTime> {
+        self.sentence.timestamp()
+    }
+
+    /// The fields of the sentence, with the original text of the ones whose value is unchanged.
+    /// Fields after the ones the sentence decodes are kept as well.
+    fn to_nmea(&self) -> Result<Nmea<'static>, ParseNMEA0183Error> {
+        let mut nmea = self.sentence.to_nmea()?;
+        for (i, field) in nmea.fields.iter_mut().enumerate() {
+            let Some(raw) = self.raw.fields.get(i) else {
+                continue;
+            };
+            if self.decoded.fields.get(i) == Some(field) {
+                *field = raw.clone();
+            } else if let Some(formatted) = like(raw, field) {
+                *field = Cow::Owned(formatted);
+            }
+        }
+        if nmea.fields.len() == self.decoded.fields.len() {
+            let extra = self.raw.fields.iter().skip(nmea.fields.len()).cloned();
+            nmea.fields.extend(extra);
+        }
+        Ok(nmea)
+    }
+}
+
+impl fmt::Display for Lossless {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.is_modified() {
+            return f.write_str(&self.line);
+        }
+        // Encoding only fails without an allocator, which lossless sentences need.
+        let mut nmea = self.to_nmea().map_err(|_| fmt::Error)?;
+        if let Some(tag_block) = self.raw_tag_block() {
+            nmea.tag_block = None;
+            f.write_str(tag_block)?;
+        }
+        write!(f, "{}", nmea)
+    }
+}
+
+#[cfg(all(test, feature = "std", feature = "all-sentences"))]
+mod tests {
+    use super::*;
+    use crate::tag_block::TagBlock;
+
+    #[test]
+    fn test_unchanged_sentences() {
+        for line in [
+            "$WIMWV,049,R,000.03,N,A*03",
+            "$WIXDR,C,+023.1,C,TEMP,P,0.9989,B,PRESS,H,040,P,RH*25",
+            "$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000",
+            "\\s:GP01,c:1700000000*2C\\$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56",
+            "$PSVSI,WIND^2cMILL^7e2*42",
+            "$PSVSI,25^B0C",
+            "$GPGSV,2,2,08,15,30,050,47,19,09,158,,26,12,281,40,27,38,173,41*7B",
+        ] {
+            let lossless = NmeaParser::parse_lossless(line).unwrap();
+            assert!(!lossless.is_modified(), "{}", line);
+            assert_eq!(lossless.to_string(), line);
+            assert_eq!(lossless.line(), line);
+
+            let nmea = lossless.to_nmea().unwrap();
+            assert_eq!(nmea, NmeaParser::to_nmea(line).unwrap(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_changed_values() {
+        let line = "$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000";
+        let mut lossless = NmeaParser::parse_lossless(line).unwrap();
+        let Sentence::Gga(gga) = &mut lossless.sentence else {
+            panic!("Expected GGA");
+        };
+        gga.num_satellites = Some(7);
+        gga.altitude_msl = Some(-9.5);
+        gga.hdop = Some(1.25);
+        assert!(lossless.is_modified());
+        assert_eq!(
+            lossless.to_string(),
+            "$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,07,1.25,-09.5,M,47.8,M,,0000*6C"
+        );
+        let reparsed = NmeaParser::parse(&lossless.to_string()).unwrap();
+        assert_eq!(reparsed, lossless.sentence);
+    }
+
+    #[test]
+    fn test_unchanged_tag_block() {
+        let tag_block = "\\c:1700000000,x:1,s:GP01*73\\";
+        let line = format!("{}$HEHDT,238.5,T", tag_block);
+        let mut lossless = NmeaParser::parse_lossless(&line).unwrap();
+        let Sentence::Hdt(hdt) = &mut lossless.sentence else {
+            panic!("Expected HDT");
+        };
+        hdt.heading = Some(240.0);
+        assert_eq!(
+            lossless.to_string(),
+            format!("{}$HEHDT,240.0,T*29", tag_block)
+        );
+        assert_ne!(
+            lossless.to_nmea().unwrap().to_string(),
+            lossless.to_string()
+        );
+    }
+
+    #[test]
+    fn test_changed_header() {
+        let line = "$WIXDR,C,+023.1,C,TEMP,P,0.9989,B,PRESS,H,040,P,RH*25";
+        let mut lossless = NmeaParser::parse_lossless(line).unwrap();
+        let Sentence::Xdr(xdr) = &mut lossless.sentence else {
+            panic!("Expected XDR");
+        };
+        xdr.tag_block = Some(TagBlock {
+            source: Some("WI01".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            lossless.to_string(),
+            "\\s:WI01*56\\$WIXDR,C,+023.1,C,TEMP,P,0.9989,B,PRESS,H,040,P,RH*25"
+        );
+        assert_eq!(lossless.into_inner().to_nmea().unwrap().fields[1], "23.1");
+    }
+}
@@ -0,0 +1,349 @@
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+};
+use core::{any::Any, fmt, ops::Deref};
+
+use crate::{
+    sentences::{error::ParseNMEA0183Error, DecodeContext, NmeaSentence},
+    storage::Cow,
+    tag_block::TagBlock,
+    talker::TalkerId,
+    Nmea, SentenceRef,
+};
+
+/// A sentence decoded by a [`SentenceDecoder`] registered outside this crate.
+///
+/// Every `Debug + Send + Sync` type can be returned, use [`dyn CustomSentence::downcast_ref`] to
+/// get the concrete type back out of [`crate::Sentence::Custom`].
+pub trait CustomSentence: fmt::Debug + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: fmt::Debug + Send + Sync + 'static> CustomSentence for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl dyn CustomSentence {
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+
+    pub fn is<T: 'static>(&self) -> bool {
+        self.as_any().is::<T>()
+    }
+}
+
+/// A [`CustomSentence`] together with the sentence it was decoded from, held by
+/// [`crate::Sentence::Custom`].
+///
+/// Dereferences to the decoded [`CustomSentence`], its [`NmeaSentence`] implementation uses the
+/// original sentence, so [`NmeaSentence::to_nmea`] returns the sentence as it was parsed.
+#[derive(Debug)]
+pub struct Custom {
+    nmea: Nmea<'static>,
+    sentence: Box<dyn CustomSentence>,
+}
+
+impl Custom {
+    pub fn new(nmea: Nmea<'_>, sentence: Box<dyn CustomSentence>) -> Self {
+        Custom {
+            nmea: nmea.into_owned(),
+            sentence,
+        }
+    }
+
+    /// The sentence the custom sentence was decoded from.
+    pub fn nmea(&self) -> &Nmea<'static> {
+        &self.nmea
+    }
+
+    pub fn into_inner(self) -> Box<dyn CustomSentence> {
+        self.sentence
+    }
+}
+
+/// Custom sentences are equal when the sentences they were decoded from are.
+impl PartialEq for Custom {
+    fn eq(&self, other: &Self) -> bool {
+        self.nmea == other.nmea
+    }
+}
+
+/// Serialized as the sentence it was decoded from, the decoded value is not serializable.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Custom {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut custom = serializer.serialize_struct("Custom", 1)?;
+        custom.serialize_field("nmea", &self.nmea)?;
+        custom.end()
+    }
+}
+
+impl Deref for Custom {
+    type Target = dyn CustomSentence;
+    fn deref(&self) -> &Self::Target {
+        &*self.sentence
+    }
+}
+
+impl NmeaSentence for Custom {
+    fn talker_id(&self) -> &TalkerId {
+        self.nmea.talker_id()
+    }
+
+    fn message_id(&self) -> Cow<'_, str> {
+        self.nmea.message_id()
+    }
+
+    fn tag_block(&self) -> Option<&TagBlock> {
+        self.nmea.tag_block()
+    }
+
+    fn to_nmea(&self) -> Result<Nmea<'static>, ParseNMEA0183Error> {
+        Ok(self.nmea.clone())
+    }
+}
+
+/// Decodes sentences this crate knows nothing about, such as in-house proprietary sentences.
+///
+/// Closures taking a [`Nmea`] and returning a [`CustomSentence`] implement this trait, implement
+/// it by hand to make use of the [`DecodeContext`] for lenient decoding.
+pub trait SentenceDecoder: Send + Sync {
+    fn decode(
+        &self,
+        nmea: Nmea<'_>,
+        ctx: &mut DecodeContext,
+    ) -> Result<Box<dyn CustomSentence>, ParseNMEA0183Error>;
+}
+
+impl<F, T> SentenceDecoder for F
+where
+    F: Fn(Nmea<'_>) -> Result<T, ParseNMEA0183Error> + Send + Sync,
+    T: CustomSentence + 'static,
+{
+    fn decode(
+        &self,
+        nmea: Nmea<'_>,
+        _ctx: &mut DecodeContext,
+    ) -> Result<Box<dyn CustomSentence>, ParseNMEA0183Error> {
+        Ok(Box::new(self(nmea)?))
+    }
+}
+
+/// Decoders for additional sentences, used by a parser created with
+/// [`crate::NmeaParser::with_registry`].
+///
+/// Registered decoders are tried before the ones built into this crate, so they can also
+/// replace the decoding of a standard sentence.
+///
+/// ```
+/// use oor_nmea0183::{
+///     registry::SentenceRegistry, sentences::error::ParseNMEA0183Error, Nmea, NmeaParser,
+///     Sentence,
+/// };
+///
+/// #[derive(Debug)]
+/// struct Depth(f32);
+///
+/// let mut registry = SentenceRegistry::new();
+/// registry.register_proprietary("XYZ", "D", |nmea: Nmea<'_>| {
+///     Ok::<_, ParseNMEA0183Error>(Depth(nmea.parse_field(0, "depth")?))
+/// });
+///
+/// let parser = NmeaParser::default().with_registry(registry);
+/// match parser.parse_line("$PXYZD,12.5").unwrap() {
+///     Sentence::Custom(sentence) => assert_eq!(sentence.downcast_ref::<Depth>().unwrap().0, 12.5),
+///     _ => panic!("Expected a custom sentence"),
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct SentenceRegistry {
+    /// Decoders by manufacturer, empty for standard sentences, and message ID, so that they
+    /// are looked up with the borrowed parts of a sentence.
+    decoders: BTreeMap<String, BTreeMap<String, Arc<dyn SentenceDecoder>>>,
+}
+
+impl SentenceRegistry {
+    pub fn new() -> Self {
+        SentenceRegistry::default()
+    }
+
+    /// Registers a decoder for a standard sentence, e.g. `DBT`, from any talker.
+    pub fn register<D: SentenceDecoder + 'static>(&mut self, message_id: &str, decoder: D) {
+        self.decoders
+            .entry(String::new())
+            .or_default()
+            .insert(message_id.to_string(), Arc::new(decoder));
+    }
+
+    /// Registers a decoder for a proprietary sentence, e.g. manufacturer `XYZ` and message ID
+    /// `D` for `$PXYZD`.
+    pub fn register_proprietary<D: SentenceDecoder + 'static>(
+        &mut self,
+        manufacturer: &str,
+        message_id: &str,
+        decoder: D,
+    ) {
+        self.decoders
+            .entry(manufacturer.to_string())
+            .or_default()
+            .insert(message_id.to_string(), Arc::new(decoder));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.decoders.is_empty()
+    }
+
+    /// Decodes with the registered decoder for the sentence if there is one, falling back to
+    /// [`SentenceRef::decode`] otherwise.
+    pub fn decode<'a>(
+        &self,
+        nmea: Nmea<'a>,
+        ctx: &mut DecodeContext,
+    ) -> Result<SentenceRef<'a>, ParseNMEA0183Error> {
+        let decoder = self
+            .decoders
+            .get(nmea.talker_id.manufacturer().unwrap_or_default())
+            .and_then(|decoders| decoders.get(nmea.message_id.as_ref()));
+        match decoder {
+            Some(decoder) => {
+                let original = nmea.clone();
+                let sentence = decoder.decode(nmea, ctx)?;
+                Ok(SentenceRef::Custom(Custom::new(original, sentence)))
+            }
+            None => SentenceRef::decode(nmea, ctx),
+        }
+    }
+}
+
+impl fmt::Debug for SentenceRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.decoders.iter().flat_map(|(manufacturer, decoders)| {
+                decoders
+                    .keys()
+                    .map(move |message_id| format!("{}{}", manufacturer, message_id))
+            }))
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "std", feature = "all-sentences"))]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{ParserConfig, UnknownSentencePolicy},
+        sentences::{error::FieldErrorKind, DecodeMode},
+        NmeaParser, Sentence,
+    };
+
+    #[derive(Debug, PartialEq)]
+    struct Status {
+        ok: Option<bool>,
+    }
+
+    struct StatusDecoder;
+
+    impl SentenceDecoder for StatusDecoder {
+        fn decode(
+            &self,
+            nmea: Nmea<'_>,
+            ctx: &mut DecodeContext,
+        ) -> Result<Box<dyn CustomSentence>, ParseNMEA0183Error> {
+            if nmea.fields.is_empty() {
+                return Err(ParseNMEA0183Error::MissingFields(1));
+            }
+            let ok = ctx.recover(match nmea.fields[0].as_ref() {
+                "A" => Ok(true),
+                "V" => Ok(false),
+                _ => Err(nmea.field_error(0, "status", FieldErrorKind::InvalidEnumerator)),
+            })?;
+            Ok(Box::new(Status { ok }))
+        }
+    }
+
+    fn parser() -> NmeaParser {
+        let mut registry = SentenceRegistry::new();
+        registry.register_proprietary("ACM", "S", StatusDecoder);
+        registry.register("HDT", |nmea: Nmea<'_>| {
+            Ok::<_, ParseNMEA0183Error>(nmea.fields[0].to_string())
+        });
+        NmeaParser::with_config(ParserConfig {
+            unknown_sentences: UnknownSentencePolicy::Error,
+            ..Default::default()
+        })
+        .with_registry(registry)
+    }
+
+    #[test]
+    fn test_custom_decoder() {
+        let parser = parser();
+        match parser.parse_line("$PACMS,A").unwrap() {
+            Sentence::Custom(sentence) => {
+                assert!(sentence.is::<Status>());
+                assert_eq!(
+                    sentence.downcast_ref::<Status>(),
+                    Some(&Status { ok: Some(true) })
+                );
+                assert_eq!(sentence.downcast_ref::<String>(), None);
+                assert_eq!(sentence.talker_id().manufacturer(), Some("ACM"));
+                assert_eq!(sentence.message_id(), "S");
+                assert_eq!(sentence.to_nmea().unwrap().to_string(), "$PACMS,A*21");
+            }
+            _ => panic!("Expected a custom sentence"),
+        }
+
+        // Registered decoders replace the built-in ones.
+        match parser.parse_line("$HEHDT,274.07,T").unwrap() {
+            Sentence::Custom(sentence) => {
+                assert_eq!(sentence.downcast_ref::<String>().unwrap(), "274.07")
+            }
+            _ => panic!("Expected a custom sentence"),
+        }
+
+        assert!(matches!(
+            parser.parse_line("$WIMWV,049,R,000.03,N,A*03"),
+            Ok(Sentence::Mwv(_))
+        ));
+        assert!(matches!(
+            parser.parse_line("$PACMT,A"),
+            Err(ParseNMEA0183Error::UnknownSentence(m)) if m == "ACMT"
+        ));
+        assert!(matches!(
+            NmeaParser::parse("$PACMS,A"),
+            Ok(Sentence::Unknown { .. })
+        ));
+    }
+
+    #[test]
+    fn test_custom_decoder_errors() {
+        let parser = parser();
+        let e = parser.parse_line("$PACMS,X").unwrap_err();
+        assert!(matches!(e, ParseNMEA0183Error::InvalidField(_)));
+        assert_eq!(e.line(), Some("$PACMS,X"));
+
+        let (sentence, warnings) = parser.parse_line_lenient("$PACMS,X").unwrap();
+        match sentence {
+            Sentence::Custom(sentence) => {
+                assert_eq!(sentence.downcast_ref(), Some(&Status { ok: None }))
+            }
+            _ => panic!("Expected a custom sentence"),
+        }
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].name, Some("status"));
+
+        let mut ctx = DecodeContext::new(DecodeMode::Strict);
+        let nmea = NmeaParser::to_nmea("$PACMS,A").unwrap();
+        assert!(matches!(
+            SentenceRegistry::new().decode(nmea, &mut ctx),
+            Ok(Sentence::Unknown { .. })
+        ));
+    }
+}
@@ -0,0 +1,432 @@
+use crate::{
+    escape,
+    sentences::error::ParseNMEA0183Error,
+    storage::{Cow, TryPush, Vec},
+    Delimiter,
+};
+
+/// The `*hh` checksum written in a sentence or TAG block, with the one computed over the data it
+/// covers.
+pub(crate) struct Checksum<'a> {
+    /// The text after the `*`, two hex digits.  The grammar allows spaces around the digits of
+    /// a sentence checksum, which then fails to parse as a number.
+    pub(crate) expected: Option<&'a str>,
+    pub(crate) computed: u8,
+}
+
+/// A sentence split into its parts by [`scan_sentence`].
+pub(crate) struct Scanned<'a> {
+    /// The parameters of the TAG block, without the enclosing `\` and checksum.
+    pub(crate) tag_block: Option<(&'a str, Checksum<'a>)>,
+    pub(crate) delimiter: Delimiter,
+    /// Index of the start delimiter in the line.
+    pub(crate) body_start: usize,
+    /// Index after the checksum, or after the fields of a sentence without one.  Like the
+    /// grammar, the scanner ignores whatever follows.
+    pub(crate) body_end: usize,
+    /// `P` for proprietary sentences.
+    pub(crate) talker_id: &'a str,
+    pub(crate) manufacturer: Option<&'a str>,
+    pub(crate) message_id: &'a str,
+    pub(crate) fields: Vec<Cow<'a, str>>,
+    pub(crate) checksum: Checksum<'a>,
+}
+
+fn is_field_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b" \"#%&'()+-./:;<=>?@[]_`{}|~".contains(&b)
+}
+
+fn is_tag_char(b: u8) -> bool {
+    (b' '..=b'~').contains(&b) && !matches!(b, b'\\' | b'*' | b'$' | b'!')
+}
+
+fn field(field: &str, escaped: bool) -> Result<Cow<'_, str>, ParseNMEA0183Error> {
+    if escaped {
+        escape::unescape(field)
+    } else {
+        Ok(Cow::Borrowed(field))
+    }
+}
+
+/// Skips the spaces the grammar allows between tokens, returning their checksum.
+fn skip_spaces(bytes: &[u8], pos: &mut usize) -> u8 {
+    let mut computed = 0;
+    while bytes.get(*pos) == Some(&b' ') {
+        computed ^= b' ';
+        *pos += 1;
+    }
+    computed
+}
+
+/// Consumes `count` alphanumeric characters with spaces between them, or `count` or more if
+/// `more`.  Spaces after the last character are not consumed.
+fn alphanumerics(bytes: &[u8], pos: &mut usize, count: usize, more: bool) -> Option<u8> {
+    let mut computed = 0;
+    let mut matched = 0;
+    let mut end = *pos;
+    while matched < count || more {
+        let mut next = end;
+        let spaces = if matched > 0 {
+            skip_spaces(bytes, &mut next)
+        } else {
+            0
+        };
+        let Some(&b) = bytes.get(next).filter(|b| b.is_ascii_alphanumeric()) else {
+            break;
+        };
+        computed ^= spaces ^ b;
+        matched += 1;
+        end = next + 1;
+    }
+    if matched < count {
+        return None;
+    }
+    *pos = end;
+    Some(computed)
+}
+
+/// The two hex digits of a `*hh` checksum starting at `pos`.
+fn checksum_at(line: &str, pos: usize) -> Option<&str> {
+    let digits = line.get(pos + 1..pos + 3)?;
+    (line.as_bytes()[pos] == b'*' && digits.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then_some(digits)
+}
+
+/// The text of a `*hh` sentence checksum starting at `pos`, with spaces allowed before either
+/// digit, and the index after it.
+fn checksum_after(line: &str, mut pos: usize) -> Option<(&str, usize)> {
+    let bytes = line.as_bytes();
+    if bytes.get(pos) != Some(&b'*') {
+        return None;
+    }
+    pos += 1;
+    let start = pos;
+    for _ in 0..2 {
+        skip_spaces(bytes, &mut pos);
+        if !bytes.get(pos).is_some_and(u8::is_ascii_hexdigit) {
+            return None;
+        }
+        pos += 1;
+    }
+    Some((&line[start..pos], pos))
+}
+
+/// Splits a sentence in a single pass over its bytes, computing the checksums on the way.
+///
+/// Accepts exactly the lines that start with a sentence matched by the `NMEA` rule of
+/// `nmea.pest`, which remains the reference for the syntax.  Like the grammar, it skips spaces
+/// between the tokens of a sentence outside of the TAG block and the fields, and before the
+/// first field.  The rule is not anchored to the end of the line, so text after the sentence is
+/// ignored, including a `*` that is not followed by two hex digits.
+pub(crate) fn scan_sentence(line: &str) -> Result<Scanned<'_>, ParseNMEA0183Error> {
+    let bytes = line.as_bytes();
+    let mut pos = 0;
+
+    let tag_block = if bytes.get(pos) == Some(&b'\\') {
+        pos += 1;
+        let params_start = pos;
+        let mut computed = 0;
+        while let Some(&b) = bytes.get(pos).filter(|&&b| is_tag_char(b)) {
+            computed ^= b;
+            pos += 1;
+        }
+        let params = &line[params_start..pos];
+        let expected = checksum_at(line, pos).ok_or(ParseNMEA0183Error::ParseGrammarError)?;
+        pos += 3;
+        if bytes.get(pos) != Some(&b'\\') {
+            return Err(ParseNMEA0183Error::ParseGrammarError);
+        }
+        pos += 1;
+        let checksum = Checksum {
+            expected: Some(expected),
+            computed,
+        };
+        Some((params, checksum))
+    } else {
+        None
+    };
+    skip_spaces(bytes, &mut pos);
+
+    let body_start = pos;
+    let delimiter = match bytes.get(pos) {
+        Some(b'$') => Delimiter::Parametric,
+        Some(b'!') => Delimiter::Encapsulation,
+        _ => return Err(ParseNMEA0183Error::ParseGrammarError),
+    };
+    pos += 1;
+
+    let mut computed = skip_spaces(bytes, &mut pos);
+    let address_start = pos;
+    let proprietary = bytes.get(pos) == Some(&b'P')
+        && bytes
+            .get(pos + 1..pos + 4)
+            .is_some_and(|manufacturer| manufacturer.iter().all(u8::is_ascii_alphanumeric));
+    let (talker_id, manufacturer, message_id) = if proprietary {
+        // The proprietary address is atomic, without spaces.
+        while let Some(&b) = bytes.get(pos).filter(|b| b.is_ascii_alphanumeric()) {
+            computed ^= b;
+            pos += 1;
+        }
+        let address = &line[address_start..pos];
+        ("P", Some(&address[1..4]), &address[4..])
+    } else {
+        computed ^= alphanumerics(bytes, &mut pos, 2, false)
+            .ok_or(ParseNMEA0183Error::ParseGrammarError)?;
+        let talker_id = &line[address_start..pos];
+        computed ^= skip_spaces(bytes, &mut pos);
+        let message_start = pos;
+        // `ASCII_ALPHANUMERIC+` keeps the spaces after its first character.
+        computed ^= alphanumerics(bytes, &mut pos, 1, false)
+            .ok_or(ParseNMEA0183Error::ParseGrammarError)?;
+        computed ^= skip_spaces(bytes, &mut pos);
+        computed ^= alphanumerics(bytes, &mut pos, 0, true).unwrap_or_default();
+        (talker_id, None, &line[message_start..pos])
+    };
+
+    computed ^= skip_spaces(bytes, &mut pos);
+    if bytes.get(pos) != Some(&b',') {
+        return Err(ParseNMEA0183Error::ParseGrammarError);
+    }
+    computed ^= b',';
+    pos += 1;
+    computed ^= skip_spaces(bytes, &mut pos);
+
+    let mut fields = Vec::new();
+    let mut field_start = pos;
+    let mut escaped = false;
+    while let Some(&b) = bytes.get(pos) {
+        if is_field_char(b) {
+            pos += 1;
+        } else if b == b',' {
+            fields.try_push(field(&line[field_start..pos], escaped)?)?;
+            pos += 1;
+            field_start = pos;
+            escaped = false;
+        } else if b == b'^'
+            && bytes
+                .get(pos + 1..pos + 3)
+                .is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+        {
+            computed ^= bytes[pos + 1] ^ bytes[pos + 2];
+            pos += 3;
+            escaped = true;
+        } else {
+            break;
+        }
+        computed ^= b;
+    }
+    // After a comma, the grammar skips the spaces after the last value instead of matching them
+    // as part of the fields.
+    let last = &line[field_start..pos];
+    let trimmed = last.trim_end_matches(' ');
+    let last = if fields.is_empty() || trimmed.is_empty() {
+        last
+    } else {
+        trimmed
+    };
+    fields.try_push(field(last, escaped)?)?;
+
+    let body_end = field_start + last.len();
+    let (expected, body_end) = match checksum_after(line, pos) {
+        Some((expected, end)) => (Some(expected), end),
+        None => (None, body_end),
+    };
+
+    Ok(Scanned {
+        tag_block,
+        delimiter,
+        body_start,
+        body_end,
+        talker_id,
+        manufacturer,
+        message_id,
+        fields,
+        checksum: Checksum { expected, computed },
+    })
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{NmeaPest, Rule};
+    use pest::Parser;
+
+    const LINES: [&str; 10] = [
+        "$WIMWV,049,R,000.03,N,A*03",
+        "$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56",
+        "$PGILT,A,+00,D,+01,D,+1,TILT*35",
+        "$PSVST,2020-10-02 16:04:53*58",
+        "$PGRM,1",
+        "!AIVDM,1,1,,A,13aEOK?P00PD2wVMdLDRhgvL289?,0*26",
+        "\\c:1700000000,s:GP01*2C\\$GPGGA,,,,,,,,,,,,,,*56",
+        "$GPTXT,01,01,02,ALARM^21 ^5EHIGH^5E TEMP*41",
+        "$IIALR,,,,~ok*25",
+        "$CCGPQ,GGA",
+    ];
+
+    /// The parts the pest grammar splits `line` into, in the same form as [`Scanned`].
+    #[allow(clippy::type_complexity)]
+    fn pest_parts(
+        line: &str,
+    ) -> Option<(
+        Option<(&str, &str)>,
+        usize,
+        &str,
+        Option<&str>,
+        &str,
+        (&str, usize),
+        Option<(usize, &str)>,
+    )> {
+        let parsed = NmeaPest::parse(Rule::NMEA, line).ok()?.next().unwrap();
+        let mut parts = (None, 0, "", None, "", ("", 0), None);
+        for pair in parsed.into_inner() {
+            match pair.as_rule() {
+                Rule::tag_block => {
+                    let mut inner = pair.into_inner();
+                    let params = inner.next().unwrap().as_str();
+                    parts.0 = Some((params, &inner.next().unwrap().as_str()[1..]));
+                }
+                Rule::start_delimiter => parts.1 = pair.as_span().start(),
+                Rule::proprietary => {
+                    let mut inner = pair.into_inner();
+                    parts.2 = "P";
+                    parts.3 = Some(inner.next().unwrap().as_str());
+                    parts.4 = inner.next().map_or("", |pair| pair.as_str());
+                }
+                Rule::talker_id => parts.2 = pair.as_str(),
+                Rule::message_type => parts.4 = pair.as_str(),
+                Rule::fields => parts.5 = (pair.as_str(), pair.as_span().end()),
+                Rule::checksum => parts.6 = Some((pair.as_span().start(), &pair.as_str()[1..])),
+                _ => {}
+            }
+        }
+        Some(parts)
+    }
+
+    fn assert_same(line: &str) {
+        let scanned = scan_sentence(line);
+        let Some((
+            tag_block,
+            body_start,
+            talker_id,
+            manufacturer,
+            message_id,
+            (fields, fields_end),
+            checksum,
+        )) = pest_parts(line)
+        else {
+            assert!(scanned.is_err(), "{:?} was accepted", line);
+            return;
+        };
+        let Ok(scanned) = scanned else {
+            panic!("{:?} was rejected", line);
+        };
+
+        assert_eq!(
+            scanned
+                .tag_block
+                .as_ref()
+                .map(|(p, c)| (*p, c.expected.unwrap())),
+            tag_block,
+            "{:?}",
+            line
+        );
+        if let Some((params, checksum)) = &scanned.tag_block {
+            assert_eq!(checksum.computed, crate::checksum(params));
+        }
+        assert_eq!(scanned.body_start, body_start, "{:?}", line);
+        assert_eq!(scanned.talker_id, talker_id, "{:?}", line);
+        assert_eq!(scanned.manufacturer, manufacturer, "{:?}", line);
+        assert_eq!(scanned.message_id, message_id, "{:?}", line);
+        let expected: Vec<_> = fields
+            .split(',')
+            .map(|f| escape::unescape(f).unwrap())
+            .collect();
+        assert_eq!(scanned.fields, expected, "{:?}", line);
+        assert_eq!(
+            scanned.checksum.expected,
+            checksum.map(|(_, checksum)| checksum),
+            "{:?}",
+            line
+        );
+        let body_end = checksum.map_or(fields_end, |(start, checksum)| start + 1 + checksum.len());
+        assert_eq!(scanned.body_end, body_end, "{:?}", line);
+        if let Some((start, _)) = checksum {
+            assert_eq!(
+                scanned.checksum.computed,
+                crate::checksum(&line[body_start + 1..start]),
+                "{:?}",
+                line
+            );
+        }
+    }
+
+    /// Every single character insertion, replacement and deletion of the sample lines must be
+    /// accepted or rejected by both the scanner and the grammar, and split the same way.
+    #[test]
+    fn test_scanner_matches_grammar() {
+        let alphabet = [
+            "$", "!", "\\", "*", ",", "^", "~", " ", "P", "A", "z", "0", "F", "\r", "\n", "\t",
+            "\x7F", "é",
+        ];
+        for line in LINES {
+            assert_same(line);
+            assert_same(&line[..line.len() - 1]);
+            for (i, _) in line.char_indices().chain([(line.len(), ' ')]) {
+                let (head, tail) = line.split_at(i);
+                let mut rest = tail.chars();
+                rest.next();
+                let rest = rest.as_str();
+                assert_same(&format!("{}{}", head, rest));
+                for c in alphabet {
+                    assert_same(&format!("{}{}{}", head, c, tail));
+                    assert_same(&format!("{}{}{}", head, c, rest));
+                }
+            }
+        }
+
+        for line in [
+            "",
+            "$",
+            "$GP",
+            "$GPG",
+            "$GPGGA",
+            "$GPGGA,",
+            "$P",
+            "$PA,",
+            "$PAB,",
+            "$PABC,",
+            "$PABCD,",
+            "$12X,",
+            "\\\\$GPGGA,",
+            "\\*00\\$GPGGA,",
+            "\\a:b*0\\$GPGGA,",
+            "$GPGGA,*",
+            "$GPGGA,*0",
+            "$GPGGA,*0G",
+            "$GPGGA,*000",
+            "$GPGGA,^",
+            "$GPGGA,^0",
+            "$GPGGA,^0G",
+            "$GPGGA,^^00",
+            " $ WI MWV , 049,R *  0 3 ",
+            "\\s:GP01*07\\ $GPGGA,",
+            "$PGIL T,",
+            "$PGI LT,",
+            "$P GILT,",
+            "$W IMWV,",
+            "$WIM WV,",
+            "$WIM W V ,A ,B  ,C  *0 3",
+            "$GPGGA,A,  ",
+            "$GPGGA,  ,A  ",
+            "$PGR ,1",
+            " \\s:GP01*07\\$GPGGA,",
+            "$WIMWV,049,R,000.03,N,A*03 trailing",
+            "$WIMWV,049,R,000.03,N,A*0",
+            "$GPGGA,A  \r\n",
+            "$GPGGA,A$GPGGA,B*00",
+        ] {
+            assert_same(line);
+        }
+    }
+}
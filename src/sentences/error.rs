@@ -1,17 +1,126 @@
+use crate::{
+    storage::{Box, String, ToString},
+    talker::TalkerId,
+};
+use core::{fmt, num};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum ParseNMEA0183Error {
-    #[error("Failed to parse field")]
-    ParseIntError(#[from] std::num::ParseIntError),
-    #[error("Failed to parse field")]
-    ParseFloatError(#[from] std::num::ParseFloatError),
     #[error("Cant convert to enum, field did not match any enumerators: {0}")]
     ConvertToEnumError(String),
+    #[error("{0}")]
+    InvalidField(Box<FieldError>),
     #[error("Cant parse NMEA0183 sentence from PEG grammar.")]
     ParseGrammarError,
+    #[error("Invalid TAG block parameter {code}:{value}")]
+    InvalidTagParameter { code: String, value: String },
     #[error("NMEA0183 checksum verification failed.")]
     NMEA0183ChecksumError,
+    #[error("NMEA0183 sentence has no checksum.")]
+    MissingChecksum,
     #[error("The NMEA0183 message should contain at least {0} fields")]
     MissingFields(u16),
+    #[error("NMEA0183 sentence is longer than {0} bytes")]
+    SentenceTooLong(usize),
+    /// Carries the [`TalkerId::prefix`] the talker filters matched.
+    #[error("Sentences from talker {0} are not accepted")]
+    TalkerFiltered(String),
+    #[error("Sentences with message ID {0} are not accepted")]
+    MessageFiltered(String),
+    #[error("No decoder for NMEA0183 message ID {0}")]
+    UnknownSentence(String),
+    /// Only returned without the `alloc` feature, see [`crate::storage`].
+    #[error("More than {0} values do not fit into fixed-capacity storage")]
+    CapacityExceeded(usize),
+    #[cfg(feature = "std")]
+    #[error("Failed to read NMEA0183 stream")]
+    Io(#[from] std::io::Error),
+}
+
+impl ParseNMEA0183Error {
+    /// The line the error occurred in, if known.
+    ///
+    /// Field errors returned by [`crate::NmeaParser`] and [`crate::framer::Framer`] carry the
+    /// line they were found in, decoding an [`crate::Nmea`] directly does not.
+    pub fn line(&self) -> Option<&str> {
+        match self {
+            ParseNMEA0183Error::InvalidField(e) => e.line.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Attaches `line` to a field error, other errors are returned unchanged.
+    pub fn with_line(self, line: &str) -> Self {
+        match self {
+            ParseNMEA0183Error::InvalidField(mut e) => {
+                e.line.get_or_insert_with(|| line.to_string());
+                ParseNMEA0183Error::InvalidField(e)
+            }
+            e => e,
+        }
+    }
+}
+
+/// A field of a sentence that could not be decoded, with enough context to find it again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub talker_id: TalkerId,
+    pub message_id: String,
+    /// Zero based index of the field, not counting the talker and message ID.
+    pub index: usize,
+    /// Name of the struct member the field decodes into.
+    pub name: Option<&'static str>,
+    /// The field's text as it appeared in the sentence.
+    pub raw: String,
+    pub kind: FieldErrorKind,
+    /// The complete line the sentence was parsed from, if known.
+    pub line: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldErrorKind {
+    InvalidInteger(num::ParseIntError),
+    InvalidFloat(num::ParseFloatError),
+    /// The field did not match any of the values allowed for it.
+    InvalidEnumerator,
+    /// The field is not written in the format of its type, such as a date and time.
+    InvalidFormat,
+}
+
+impl From<num::ParseIntError> for FieldErrorKind {
+    fn from(e: num::ParseIntError) -> Self {
+        FieldErrorKind::InvalidInteger(e)
+    }
+}
+
+impl From<num::ParseFloatError> for FieldErrorKind {
+    fn from(e: num::ParseFloatError) -> Self {
+        FieldErrorKind::InvalidFloat(e)
+    }
+}
+
+impl fmt::Display for FieldErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldErrorKind::InvalidInteger(e) => write!(f, "{}", e),
+            FieldErrorKind::InvalidFloat(e) => write!(f, "{}", e),
+            FieldErrorKind::InvalidEnumerator => write!(f, "field did not match any enumerators"),
+            FieldErrorKind::InvalidFormat => write!(f, "field is not in the expected format"),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to parse field {} of {}{}",
+            self.index, self.talker_id, self.message_id
+        )?;
+        if let Some(name) = self.name {
+            write!(f, " ({})", name)?;
+        }
+        write!(f, ": {} in `{}`", self.kind, self.raw)
+    }
 }
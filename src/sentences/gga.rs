@@ -1,5 +1,15 @@
-use super::error::ParseNMEA0183Error;
-use crate::Nmea;
+use super::{
+    encode,
+    error::{FieldErrorKind, ParseNMEA0183Error},
+    field, sentence_header, DecodeContext, NmeaSentence,
+};
+use crate::{
+    field::{FieldMeta, FieldType, NmeaField, SentenceMeta},
+    storage::{format, Cow, ToString},
+    tag_block::TagBlock,
+    talker::TalkerId,
+    Nmea,
+};
 
 /// Represents the `$GPGGA` (Global Positioning System Fix Data) NMEA 0183 sentence.
 ///
@@ -9,9 +19,12 @@ use crate::Nmea;
 /// ### Fields:
 /// - `talker_id`: The talker ID of the sentence (e.g., `GP` for GPS).
 /// - `message_id`: The message ID, typically `GGA`.
+/// - `tag_block`: The TAG block the sentence was received with, if any.
 /// - `fix_time`: Optional floating-point value representing the UTC time in `hhmmss.sss` format (e.g., `161229.487` for 16:12:29.487).
 /// - `latitude`: Optional floating-point value representing the latitude in `ddmm.mmmm` format (e.g., `3723.2475` for 37°23.2475').
+/// - `latitude_hemisphere`: Optional `Hemisphere` of the latitude, `North` or `South`.
 /// - `longitude`: Optional floating-point value representing the longitude in `ddmm.mmmm` format (e.g., `12158.3416` for 121°58.3416').
+/// - `longitude_hemisphere`: Optional `Hemisphere` of the longitude, `East` or `West`.
 /// - `fix_quality`: Optional `FixQuality` enum indicating the quality of the GPS fix:
 ///   - `FixNotValid`: Fix not valid.
 ///   - `GpsFix`: Standard GPS fix.
@@ -33,13 +46,19 @@ use crate::Nmea;
 /// ### Conversion:
 /// Implements [`TryFrom<Nmea>`] to parse the `$GPGGA` sentence into a `Gga` struct.
 /// The conversion extracts all relevant fields and parses them into the corresponding struct members.
-#[derive(Debug, Clone)]
+/// When encoded, the time and position are padded with leading zeros to `hhmmss`, `ddmm` and
+/// `dddmm`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Gga {
-    pub talker_id: String,
-    pub message_id: String,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'static, str>,
+    pub tag_block: Option<TagBlock>,
     pub fix_time: Option<f32>, // Convert to a time
     pub latitude: Option<f64>,
+    pub latitude_hemisphere: Option<Hemisphere>,
     pub longitude: Option<f64>,
+    pub longitude_hemisphere: Option<Hemisphere>,
     pub fix_quality: Option<FixQuality>,
     pub num_satellites: Option<u32>,
     pub hdop: Option<f32>,
@@ -49,43 +68,215 @@ pub struct Gga {
     pub differential_gps_reference_station_id: Option<u32>,
 }
 
-impl TryFrom<Nmea> for Gga {
+impl TryFrom<Nmea<'_>> for Gga {
     type Error = ParseNMEA0183Error;
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
+    fn try_from(nmea: Nmea<'_>) -> Result<Self, Self::Error> {
+        Gga::decode(nmea, &mut DecodeContext::default())
+    }
+}
+
+impl Gga {
+    /// Decodes the sentence, `ctx` decides whether an invalid field value rejects the sentence.
+    pub fn decode(nmea: Nmea<'_>, ctx: &mut DecodeContext) -> Result<Self, ParseNMEA0183Error> {
+        if nmea.fields.len() < 14 {
+            return Err(ParseNMEA0183Error::MissingFields(14));
+        }
+
+        let fix_quality = ctx.recover(nmea.parse_field::<u8>(5, "fix_quality").and_then(
+            |fix_quality| match fix_quality {
+                0 => Ok(FixQuality::FixNotValid),
+                1 => Ok(FixQuality::GpsFix),
+                2 => Ok(FixQuality::DifferentialGpsFix),
+                3 => Ok(FixQuality::NotApplicable),
+                4 => Ok(FixQuality::RtkFixed),
+                5 => Ok(FixQuality::RtkFloat),
+                6 => Ok(FixQuality::InsDeadReckoning),
+                _ => Err(nmea.field_error(5, "fix_quality", FieldErrorKind::InvalidEnumerator)),
+            },
+        ))?;
+
+        // The header is moved out of `nmea` last, after the fields have been decoded from it.
         let mwv = Gga {
+            fix_time: ctx.optional(&nmea, 0, "fix_time")?,
+            latitude: ctx.optional(&nmea, 1, "latitude")?,
+            latitude_hemisphere: ctx.optional(&nmea, 2, "latitude_hemisphere")?,
+            longitude: ctx.optional(&nmea, 3, "longitude")?,
+            longitude_hemisphere: ctx.optional(&nmea, 4, "longitude_hemisphere")?,
+            fix_quality,
+            num_satellites: ctx.optional(&nmea, 6, "num_satellites")?,
+            hdop: ctx.optional(&nmea, 7, "hdop")?,
+            altitude_msl: ctx.optional(&nmea, 8, "altitude_msl")?,
+            geoid_separation: ctx.optional(&nmea, 10, "geoid_separation")?,
+            differential_age: ctx.optional(&nmea, 12, "differential_age")?,
+            differential_gps_reference_station_id: ctx.optional(
+                &nmea,
+                13,
+                "differential_gps_reference_station_id",
+            )?,
             talker_id: nmea.talker_id,
-            message_id: nmea.message_id,
-            fix_time: nmea.fields[0].parse::<f32>().ok(),
-            latitude: nmea.fields[1].parse::<f64>().ok(),
-            longitude: nmea.fields[3].parse::<f64>().ok(),
-            fix_quality: Some(match nmea.fields[5].parse::<u8>()? {
-                0 => FixQuality::FixNotValid,
-                1 => FixQuality::GpsFix,
-                2 => FixQuality::DifferentialGpsFix,
-                3 => FixQuality::NotApplicable,
-                4 => FixQuality::RtkFixed,
-                5 => FixQuality::RtkFloat,
-                6 => FixQuality::InsDeadReckoning,
-                field => return Err(ParseNMEA0183Error::ConvertToEnumError(field.to_string())),
-            }),
-            num_satellites: nmea.fields[6].parse::<u32>().ok(),
-            hdop: nmea.fields[7].parse::<f32>().ok(),
-            altitude_msl: nmea.fields[8].parse::<f32>().ok(),
-            geoid_separation: nmea.fields[10].parse::<f32>().ok(),
-            differential_age: nmea.fields[12].parse::<f32>().ok(),
-            differential_gps_reference_station_id: nmea.fields[13].parse::<u32>().ok(),
+            message_id: Cow::Borrowed(Self::MESSAGE_ID),
+            tag_block: nmea.tag_block,
         };
         Ok(mwv)
     }
 }
 
+impl SentenceMeta for Gga {
+    const MANUFACTURER: Option<&'static str> = None;
+    const MESSAGE_ID: &'static str = "GGA";
+    const FIELDS: &'static [FieldMeta] = &[
+        FieldMeta::new("fix_time", 0, FieldType::Float)
+            .unit("hhmmss")
+            .nullable(),
+        FieldMeta::new("latitude", 1, FieldType::Float)
+            .unit("ddmm")
+            .nullable(),
+        FieldMeta::new("latitude_hemisphere", 2, FieldType::Enum(&["N", "S"])).nullable(),
+        FieldMeta::new("longitude", 3, FieldType::Float)
+            .unit("dddmm")
+            .nullable(),
+        FieldMeta::new("longitude_hemisphere", 4, FieldType::Enum(&["E", "W"])).nullable(),
+        FieldMeta::new(
+            "fix_quality",
+            5,
+            FieldType::Enum(&["0", "1", "2", "3", "4", "5", "6"]),
+        )
+        .option(),
+        FieldMeta::new("num_satellites", 6, FieldType::Integer).nullable(),
+        FieldMeta::new("hdop", 7, FieldType::Float).nullable(),
+        FieldMeta::new("altitude_msl", 8, FieldType::Float)
+            .unit("meter")
+            .nullable(),
+        FieldMeta::new("altitude_units", 9, FieldType::Enum(&["M"])).nullable(),
+        FieldMeta::new("geoid_separation", 10, FieldType::Float)
+            .unit("meter")
+            .nullable(),
+        FieldMeta::new("geoid_separation_units", 11, FieldType::Enum(&["M"])).nullable(),
+        FieldMeta::new("differential_age", 12, FieldType::Float)
+            .unit("second")
+            .nullable(),
+        FieldMeta::new(
+            "differential_gps_reference_station_id",
+            13,
+            FieldType::Integer,
+        )
+        .nullable(),
+    ];
+}
+
+impl NmeaSentence for Gga {
+    sentence_header!();
+
+    fn to_nmea(&self) -> Result<Nmea<'static>, ParseNMEA0183Error> {
+        let meters = |value: Option<f32>| field(value.map(|_| 'M'));
+        encode(
+            self,
+            [
+                padded(self.fix_time, 6),
+                padded(self.latitude, 4),
+                field(self.latitude_hemisphere.as_ref().map(Hemisphere::as_char)),
+                padded(self.longitude, 5),
+                field(self.longitude_hemisphere.as_ref().map(Hemisphere::as_char)),
+                field(self.fix_quality.as_ref().map(FixQuality::as_u8)),
+                field(self.num_satellites),
+                field(self.hdop),
+                field(self.altitude_msl),
+                meters(self.altitude_msl),
+                field(self.geoid_separation),
+                meters(self.geoid_separation),
+                field(self.differential_age),
+                field(self.differential_gps_reference_station_id),
+            ],
+        )
+    }
+}
+
+/// Formats a number with at least `digits` digits before the decimal point, as the `hhmmss`,
+/// `ddmm` and `dddmm` fields are written.
+fn padded(value: Option<impl core::fmt::Display>, digits: usize) -> Cow<'static, str> {
+    let Some(value) = value else {
+        return Cow::Borrowed("");
+    };
+    let value = value.to_string();
+    let whole = value.find('.').unwrap_or(value.len());
+    Cow::Owned(format!(
+        "{:0>width$}",
+        value,
+        width = value.len() + digits.saturating_sub(whole)
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Hemisphere {
+    #[cfg_attr(feature = "serde", serde(rename = "N"))]
+    North,
+    #[cfg_attr(feature = "serde", serde(rename = "S"))]
+    South,
+    #[cfg_attr(feature = "serde", serde(rename = "E"))]
+    East,
+    #[cfg_attr(feature = "serde", serde(rename = "W"))]
+    West,
+}
+
+impl Hemisphere {
+    pub fn as_char(&self) -> char {
+        match self {
+            Hemisphere::North => 'N',
+            Hemisphere::South => 'S',
+            Hemisphere::East => 'E',
+            Hemisphere::West => 'W',
+        }
+    }
+}
+
+impl NmeaField for Hemisphere {
+    const TYPE: FieldType = FieldType::Enum(&["N", "S", "E", "W"]);
+
+    fn decode_field(field: &str) -> Result<Self, FieldErrorKind> {
+        match field {
+            "N" => Ok(Hemisphere::North),
+            "S" => Ok(Hemisphere::South),
+            "E" => Ok(Hemisphere::East),
+            "W" => Ok(Hemisphere::West),
+            _ => Err(FieldErrorKind::InvalidEnumerator),
+        }
+    }
+
+    fn encode_field(&self) -> Cow<'static, str> {
+        field(Some(self.as_char()))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FixQuality {
+    #[cfg_attr(feature = "serde", serde(rename = "0"))]
     FixNotValid,
+    #[cfg_attr(feature = "serde", serde(rename = "1"))]
     GpsFix,
+    #[cfg_attr(feature = "serde", serde(rename = "2"))]
     DifferentialGpsFix,
+    #[cfg_attr(feature = "serde", serde(rename = "3"))]
     NotApplicable,
+    #[cfg_attr(feature = "serde", serde(rename = "4"))]
     RtkFixed,
+    #[cfg_attr(feature = "serde", serde(rename = "5"))]
     RtkFloat,
+    #[cfg_attr(feature = "serde", serde(rename = "6"))]
     InsDeadReckoning,
 }
+
+impl FixQuality {
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            FixQuality::FixNotValid => 0,
+            FixQuality::GpsFix => 1,
+            FixQuality::DifferentialGpsFix => 2,
+            FixQuality::NotApplicable => 3,
+            FixQuality::RtkFixed => 4,
+            FixQuality::RtkFloat => 5,
+            FixQuality::InsDeadReckoning => 6,
+        }
+    }
+}
@@ -1,39 +1,49 @@
-use super::error::ParseNMEA0183Error;
-use crate::Nmea;
+use super::error::{FieldErrorKind, ParseNMEA0183Error};
+use crate::{
+    field::{FieldType, NmeaField},
+    storage::{Cow, ToString},
+    tag_block::TagBlock,
+    talker::TalkerId,
+    NmeaSentence,
+};
 
 /// Represents WIHDM a heading measurement with magnetic heading type.
 ///
 /// Fields:
 /// - `talker_id`: The talker ID identifying the source of the NMEA sentence.
 /// - `message_id`: The message ID identifying the type of NMEA sentence.
+/// - `tag_block`: The TAG block the sentence was received with, if any.
 /// - `heading`: The heading value in degrees, which may be `None` if not available.
 /// - `heading_type`: The type of heading, either `Magnetic` or `True`.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, NmeaSentence)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[nmea(message_id = "HDM")]
 pub struct Hdm {
-    pub talker_id: String,
-    pub message_id: String,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'static, str>,
+    pub tag_block: Option<TagBlock>,
     pub heading: Option<f32>,
     pub heading_type: Option<HeadingType>,
 }
 
-impl TryFrom<Nmea> for Hdm {
-    type Error = ParseNMEA0183Error;
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
-        Ok(Hdm {
-            talker_id: nmea.talker_id,
-            message_id: nmea.message_id,
-            heading: nmea.fields[0].parse::<f32>().ok(),
-            heading_type: nmea.fields[1].as_str().try_into().ok(),
-        })
-    }
-}
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HeadingType {
+    #[cfg_attr(feature = "serde", serde(rename = "M"))]
     Magentic,
+    #[cfg_attr(feature = "serde", serde(rename = "T"))]
     True,
 }
 
+impl HeadingType {
+    pub fn as_char(&self) -> char {
+        match self {
+            HeadingType::Magentic => 'M',
+            HeadingType::True => 'T',
+        }
+    }
+}
+
 impl TryFrom<&str> for HeadingType {
     type Error = ParseNMEA0183Error;
     fn try_from(heading_type: &str) -> Result<Self, Self::Error> {
@@ -48,3 +58,20 @@ impl TryFrom<&str> for HeadingType {
         })
     }
 }
+
+impl NmeaField for HeadingType {
+    const TYPE: FieldType = FieldType::Enum(&["M", "T"]);
+
+    fn decode_field(field: &str) -> Result<Self, FieldErrorKind> {
+        field
+            .try_into()
+            .map_err(|_| FieldErrorKind::InvalidEnumerator)
+    }
+
+    fn encode_field(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            HeadingType::Magentic => "M",
+            HeadingType::True => "T",
+        })
+    }
+}
@@ -1,22 +1,13 @@
-use super::{error::ParseNMEA0183Error, hdm::HeadingType};
-use crate::Nmea;
+use super::hdm::HeadingType;
+use crate::{storage::Cow, tag_block::TagBlock, talker::TalkerId, NmeaSentence};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, NmeaSentence)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[nmea(message_id = "HDT")]
 pub struct Hdt {
-    pub talker_id: String,
-    pub message_id: String,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'static, str>,
+    pub tag_block: Option<TagBlock>,
     pub heading: Option<f32>,
     pub heading_type: Option<HeadingType>,
 }
-
-impl TryFrom<Nmea> for Hdt {
-    type Error = ParseNMEA0183Error;
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
-        Ok(Hdt {
-            talker_id: nmea.talker_id,
-            message_id: nmea.message_id,
-            heading: nmea.fields[0].parse::<f32>().ok(),
-            heading_type: nmea.fields[1].as_str().try_into().ok(),
-        })
-    }
-}
@@ -1,47 +1,100 @@
-use super::error::ParseNMEA0183Error;
-use crate::Nmea;
+use super::{
+    encode,
+    error::{FieldErrorKind, ParseNMEA0183Error},
+    field, sentence_header, NmeaSentence,
+};
+use crate::{storage::String, tag_block::TagBlock, talker::TalkerId, Nmea};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ilt {
-    pub talker_id: String,
+    pub talker_id: TalkerId,
     pub message_id: String,
+    pub tag_block: Option<TagBlock>,
     pub wind_direction_deg: Option<i32>,
     pub wind_dir_type: Option<WindDirectionType>,
     pub wind_speed_knots: Option<f32>,
     pub acceptable: Option<AcceptableMeasurement>,
 }
 
-impl TryFrom<Nmea> for Ilt {
+impl TryFrom<Nmea<'_>> for Ilt {
     type Error = ParseNMEA0183Error;
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
+    fn try_from(nmea: Nmea<'_>) -> Result<Self, Self::Error> {
+        if nmea.fields.len() < 5 {
+            return Err(ParseNMEA0183Error::MissingFields(5));
+        }
+
+        let wind_dir_type = match nmea.fields[1].as_ref() {
+            "R" => WindDirectionType::Relative,
+            "T" => WindDirectionType::True,
+            _ => {
+                return Err(nmea.field_error(1, "wind_dir_type", FieldErrorKind::InvalidEnumerator))
+            }
+        };
+        let acceptable = match nmea.fields[4].as_ref() {
+            "A" => AcceptableMeasurement::Acceptable,
+            "V" => AcceptableMeasurement::Void,
+            _ => return Err(nmea.field_error(4, "acceptable", FieldErrorKind::InvalidEnumerator)),
+        };
+
         let mwv = Ilt {
             talker_id: nmea.talker_id,
-            message_id: nmea.message_id,
+            message_id: nmea.message_id.into_owned(),
+            tag_block: nmea.tag_block,
             wind_direction_deg: nmea.fields[0].parse::<i32>().ok(),
-            wind_dir_type: Some(match nmea.fields[1].as_str() {
-                "R" => WindDirectionType::Relative,
-                "T" => WindDirectionType::True,
-                field => return Err(ParseNMEA0183Error::ConvertToEnumError(field.to_string())),
-            }),
+            wind_dir_type: Some(wind_dir_type),
             wind_speed_knots: nmea.fields[2].parse::<f32>().ok(),
-            acceptable: Some(match nmea.fields[4].as_str() {
-                "A" => AcceptableMeasurement::Acceptable,
-                "V" => AcceptableMeasurement::Void,
-                field => return Err(ParseNMEA0183Error::ConvertToEnumError(field.to_string())),
-            }),
+            acceptable: Some(acceptable),
         };
         Ok(mwv)
     }
 }
 
-#[derive(Debug, Clone)]
+impl NmeaSentence for Ilt {
+    sentence_header!();
+
+    fn to_nmea(&self) -> Result<Nmea<'static>, ParseNMEA0183Error> {
+        encode(
+            self,
+            [
+                field(self.wind_direction_deg),
+                field(self.wind_dir_type.as_ref().map(WindDirectionType::as_char)),
+                field(self.wind_speed_knots),
+                field(Some('N')),
+                field(self.acceptable.as_ref().map(AcceptableMeasurement::as_char)),
+            ],
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WindDirectionType {
     Relative,
     True,
 }
 
-#[derive(Debug, Clone)]
+impl WindDirectionType {
+    pub fn as_char(&self) -> char {
+        match self {
+            WindDirectionType::Relative => 'R',
+            WindDirectionType::True => 'T',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AcceptableMeasurement {
     Acceptable,
     Void,
 }
+
+impl AcceptableMeasurement {
+    pub fn as_char(&self) -> char {
+        match self {
+            AcceptableMeasurement::Acceptable => 'A',
+            AcceptableMeasurement::Void => 'V',
+        }
+    }
+}
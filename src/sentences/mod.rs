@@ -1,50 +1,296 @@
 pub mod error;
+#[cfg(feature = "gnss")]
 pub mod gga;
+#[cfg(feature = "heading")]
 pub mod hdm;
+#[cfg(feature = "heading")]
 pub mod hdt;
+#[cfg(feature = "wind")]
 pub mod ilt;
+#[cfg(feature = "wind")]
 pub mod mwv;
+#[cfg(feature = "gill")]
 pub mod pgilt;
+pub mod query;
+#[cfg(feature = "seaview")]
 pub mod seaview;
+#[cfg(feature = "transducer")]
 pub mod xdr;
 
+#[cfg(any(feature = "wind", feature = "transducer"))]
+use crate::field::{FieldType, NmeaField};
+#[cfg(any(feature = "transducer", feature = "gill"))]
+use crate::storage::String;
+#[cfg(any(
+    feature = "gnss",
+    feature = "wind",
+    feature = "transducer",
+    feature = "gill",
+    feature = "seaview"
+))]
+use crate::storage::ToString;
+use crate::{
+    config::EncodeOptions,
+    storage::{self, Cow, List, TryPush},
+    tag_block::TagBlock,
+    talker::TalkerId,
+    Encoded, Nmea,
+};
+#[cfg(feature = "chrono")]
+use chrono::NaiveDateTime;
+use error::{FieldError, ParseNMEA0183Error};
+
+/// What every sentence has in common, so that sentences can be routed and logged without
+/// matching on their type.  Implemented by every sentence struct, by [`Nmea`] and by
+/// [`crate::Sentence`], which delegates to the sentence it holds.
+pub trait NmeaSentence {
+    fn talker_id(&self) -> &TalkerId;
+
+    /// The message ID, e.g. `GGA`.  For proprietary sentences this is what follows the
+    /// manufacturer mnemonic, e.g. `T` for `$PGILT`, and for queries it is the talker ID being
+    /// queried followed by `Q`.
+    fn message_id(&self) -> Cow<'_, str>;
+
+    fn tag_block(&self) -> Option<&TagBlock>;
+
+    /// When the sentence was produced: the date and time in the sentence itself if it carries
+    /// one, otherwise the `c:` time of its TAG block.  Needs the `chrono` feature.
+    #[cfg(feature = "chrono")]
+    fn timestamp(&self) -> Option<NaiveDateTime> {
+        self.tag_block().and_then(TagBlock::timestamp)
+    }
+
+    /// Encodes the sentence back into its fields, write it out with [`core::fmt::Display`].
+    ///
+    /// Without the `alloc` feature this fails with [`ParseNMEA0183Error::CapacityExceeded`]
+    /// rather than dropping the fields that do not fit.
+    fn to_nmea(&self) -> Result<Nmea<'static>, ParseNMEA0183Error>;
+
+    /// Encodes the sentence as a line with a checksum, formatted following `options`.  Write it
+    /// out with [`core::fmt::Display`].
+    fn encode(&self, options: &EncodeOptions) -> Result<Encoded, ParseNMEA0183Error> {
+        Ok(Encoded::new(self.to_nmea()?, options))
+    }
+}
+
+/// Implements the [`NmeaSentence`] accessors of a sentence struct with `talker_id`,
+/// `message_id` and `tag_block` members.
+#[cfg(any(
+    feature = "gnss",
+    feature = "wind",
+    feature = "transducer",
+    feature = "gill",
+    feature = "seaview"
+))]
+macro_rules! sentence_header {
+    () => {
+        fn talker_id(&self) -> &$crate::talker::TalkerId {
+            &self.talker_id
+        }
+
+        fn message_id(&self) -> $crate::storage::Cow<'_, str> {
+            $crate::storage::Cow::Borrowed(&self.message_id)
+        }
+
+        fn tag_block(&self) -> Option<&$crate::tag_block::TagBlock> {
+            self.tag_block.as_ref()
+        }
+    };
+}
+#[cfg(any(
+    feature = "gnss",
+    feature = "wind",
+    feature = "transducer",
+    feature = "gill",
+    feature = "seaview"
+))]
+pub(crate) use sentence_header;
+
+/// Formats a decoded value as a field, `None` is written as an empty field.
+#[cfg(any(
+    feature = "gnss",
+    feature = "wind",
+    feature = "transducer",
+    feature = "gill",
+    feature = "seaview"
+))]
+pub(crate) fn field(value: Option<impl core::fmt::Display>) -> Cow<'static, str> {
+    Cow::Owned(value.map(|value| value.to_string()).unwrap_or_default())
+}
+
+#[cfg(any(
+    feature = "gnss",
+    feature = "wind",
+    feature = "transducer",
+    feature = "gill",
+    feature = "seaview"
+))]
+pub(crate) use crate::field::encode;
+
+/// How decoders treat a field whose value is not allowed, such as an unknown status letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecodeMode {
+    /// The whole sentence is rejected.
+    #[default]
+    Strict,
+    /// The field is decoded as `None` and a warning is recorded.
+    Lenient,
+}
+
+/// Collects the non-fatal field warnings raised while decoding in [`DecodeMode::Lenient`].
+#[derive(Debug, Default)]
+pub struct DecodeContext {
+    mode: DecodeMode,
+    warnings: List<FieldError>,
+}
+
+impl DecodeContext {
+    pub fn new(mode: DecodeMode) -> Self {
+        DecodeContext {
+            mode,
+            warnings: List::new(),
+        }
+    }
+
+    /// Passes a decoded field through.  A field error is returned in strict mode and turned into
+    /// a warning and `None` in lenient mode.  Without the `alloc` feature decoding fails with
+    /// [`ParseNMEA0183Error::CapacityExceeded`] after [`storage::LIST_CAPACITY`] warnings.
+    pub fn recover<T>(
+        &mut self,
+        field: Result<T, ParseNMEA0183Error>,
+    ) -> Result<Option<T>, ParseNMEA0183Error> {
+        match field {
+            Ok(value) => Ok(Some(value)),
+            Err(ParseNMEA0183Error::InvalidField(e)) if self.mode == DecodeMode::Lenient => {
+                self.warnings.try_push(storage::unbox(e))?;
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Decodes field `index` with [`crate::field::optional`], an invalid value is recovered
+    /// from like with [`DecodeContext::recover`].
+    pub fn optional<T: crate::field::NmeaField>(
+        &mut self,
+        nmea: &Nmea<'_>,
+        index: usize,
+        name: &'static str,
+    ) -> Result<Option<T>, ParseNMEA0183Error> {
+        Ok(self
+            .recover(crate::field::optional(nmea, index, name))?
+            .flatten())
+    }
+
+    pub fn warnings(&self) -> &[FieldError] {
+        &self.warnings
+    }
+
+    pub fn into_warnings(self) -> List<FieldError> {
+        self.warnings
+    }
+}
+
+#[cfg(feature = "wind")]
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnitsOfSpeed {
+    #[cfg_attr(feature = "serde", serde(rename = "K"))]
     KilometersPerHour,
+    #[cfg_attr(feature = "serde", serde(rename = "M"))]
     MetresPerSecond,
+    #[cfg_attr(feature = "serde", serde(rename = "N"))]
     Knots,
+    #[cfg_attr(feature = "serde", serde(rename = "S"))]
     StatuteMilesPerHour,
 }
 
+#[cfg(feature = "wind")]
 impl UnitsOfSpeed {
-    fn from_char(c: &str) -> Option<Self> {
-        match c {
-            "K" => Some(Self::KilometersPerHour),
-            "M" => Some(Self::MetresPerSecond),
-            "N" => Some(Self::Knots),
-            "S" => Some(Self::StatuteMilesPerHour),
-            _ => None,
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::KilometersPerHour => 'K',
+            Self::MetresPerSecond => 'M',
+            Self::Knots => 'N',
+            Self::StatuteMilesPerHour => 'S',
         }
     }
 }
 
+#[cfg(feature = "wind")]
+impl NmeaField for UnitsOfSpeed {
+    const TYPE: FieldType = FieldType::Enum(&["K", "M", "N", "S"]);
+
+    fn decode_field(field: &str) -> Result<Self, error::FieldErrorKind> {
+        match field {
+            "K" => Ok(Self::KilometersPerHour),
+            "M" => Ok(Self::MetresPerSecond),
+            "N" => Ok(Self::Knots),
+            "S" => Ok(Self::StatuteMilesPerHour),
+            _ => Err(error::FieldErrorKind::InvalidEnumerator),
+        }
+    }
+
+    fn encode_field(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            Self::KilometersPerHour => "K",
+            Self::MetresPerSecond => "M",
+            Self::Knots => "N",
+            Self::StatuteMilesPerHour => "S",
+        })
+    }
+}
+
+#[cfg(any(feature = "transducer", feature = "gill"))]
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnitsOfMeasurement {
+    #[cfg_attr(feature = "serde", serde(rename = "A"))]
     Amperes,
+    #[cfg_attr(feature = "serde", serde(rename = "B"))]
     Bar,
+    #[cfg_attr(feature = "serde", serde(rename = "C"))]
     Celsius,
+    #[cfg_attr(feature = "serde", serde(rename = "D"))]
     Degrees,
+    #[cfg_attr(feature = "serde", serde(rename = "H"))]
     Hertz,
+    #[cfg_attr(feature = "serde", serde(rename = "K"))]
     Kelvin,
+    #[cfg_attr(feature = "serde", serde(rename = "I"))]
     LitresPerSecond,
+    #[cfg_attr(feature = "serde", serde(rename = "M"))]
     Meter,
+    #[cfg_attr(feature = "serde", serde(rename = "N"))]
     Newton,
+    #[cfg_attr(feature = "serde", serde(rename = "S"))]
     PartsPerThousand,
+    #[cfg_attr(feature = "serde", serde(rename = "P"))]
     Percent,
+    #[cfg_attr(feature = "serde", serde(rename = "V"))]
     Volts,
 }
 
+#[cfg(any(feature = "transducer", feature = "gill"))]
 impl UnitsOfMeasurement {
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::Amperes => 'A',
+            Self::Bar => 'B',
+            Self::Celsius => 'C',
+            Self::Degrees => 'D',
+            Self::Hertz => 'H',
+            Self::LitresPerSecond => 'I',
+            Self::Kelvin => 'K',
+            Self::Meter => 'M',
+            Self::Newton => 'N',
+            Self::Percent => 'P',
+            Self::PartsPerThousand => 'S',
+            Self::Volts => 'V',
+        }
+    }
+
+    #[cfg(feature = "transducer")]
     fn from_char(c: &str) -> Option<Self> {
         match c {
             "A" => Some(Self::Amperes),
@@ -64,6 +310,20 @@ impl UnitsOfMeasurement {
     }
 }
 
+#[cfg(feature = "transducer")]
+impl NmeaField for UnitsOfMeasurement {
+    const TYPE: FieldType =
+        FieldType::Enum(&["A", "B", "C", "D", "H", "I", "K", "M", "N", "P", "S", "V"]);
+
+    fn decode_field(field: &str) -> Result<Self, error::FieldErrorKind> {
+        Self::from_char(field).ok_or(error::FieldErrorKind::InvalidEnumerator)
+    }
+
+    fn encode_field(&self) -> Cow<'static, str> {
+        field(Some(self.as_char()))
+    }
+}
+
 /// Some messages can contain multiple types of transducer data.  It makes treanslation a bit of a pain in
 /// the ass.  Here, we return a struct containing optional data from each possible transducer type
 /// - one of them will have what you're looking for.  Right now we only support A and C.
@@ -75,20 +335,72 @@ impl UnitsOfMeasurement {
 ///   N   Force
 ///   P   Pressure
 ///   R   Flow
-#[derive(Debug, Clone)]
+#[cfg(any(feature = "transducer", feature = "gill"))]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
 pub enum TransducerReading {
+    #[cfg_attr(feature = "serde", serde(rename = "A"))]
     AngularDisplacement(Reading),
+    #[cfg_attr(feature = "serde", serde(rename = "C"))]
     Temperature(Reading),
+    #[cfg_attr(feature = "serde", serde(rename = "D"))]
     Depth(Reading),
+    #[cfg_attr(feature = "serde", serde(rename = "F"))]
     Frequency(Reading),
+    #[cfg_attr(feature = "serde", serde(rename = "H"))]
     Humidity(Reading),
+    #[cfg_attr(feature = "serde", serde(rename = "N"))]
     Force(Reading),
+    #[cfg_attr(feature = "serde", serde(rename = "P"))]
     Pressure(Reading),
+    #[cfg_attr(feature = "serde", serde(rename = "R"))]
     Flow(Reading),
+    /// A transducer type not listed above, such as `G` for a generic one, with the type as
+    /// it was sent so that the reading is written back unchanged.
+    #[cfg_attr(feature = "serde", serde(untagged))]
+    Other {
+        #[cfg_attr(feature = "serde", serde(rename = "type"))]
+        kind: String,
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        reading: Reading,
+    },
 }
 
+#[cfg(any(feature = "transducer", feature = "gill"))]
 impl TransducerReading {
-    fn from_nmea(c: &str, reading: Reading) -> Option<Self> {
+    /// The transducer type as written in an `XDR` sentence.
+    pub fn kind(&self) -> &str {
+        match self {
+            Self::AngularDisplacement(_) => "A",
+            Self::Temperature(_) => "C",
+            Self::Depth(_) => "D",
+            Self::Frequency(_) => "F",
+            Self::Humidity(_) => "H",
+            Self::Force(_) => "N",
+            Self::Pressure(_) => "P",
+            Self::Flow(_) => "R",
+            Self::Other { kind, .. } => kind,
+        }
+    }
+
+    pub fn reading(&self) -> &Reading {
+        match self {
+            Self::AngularDisplacement(reading)
+            | Self::Temperature(reading)
+            | Self::Depth(reading)
+            | Self::Frequency(reading)
+            | Self::Humidity(reading)
+            | Self::Force(reading)
+            | Self::Pressure(reading)
+            | Self::Flow(reading)
+            | Self::Other { reading, .. } => reading,
+        }
+    }
+
+    /// The reading of transducer type `c`, `None` if the type is empty.
+    #[cfg(feature = "transducer")]
+    pub(crate) fn from_nmea(c: &str, reading: Reading) -> Option<Self> {
         match c {
             "A" => Some(Self::AngularDisplacement(reading)),
             "C" => Some(Self::Temperature(reading)),
@@ -98,12 +410,18 @@ impl TransducerReading {
             "N" => Some(Self::Force(reading)),
             "P" => Some(Self::Pressure(reading)),
             "R" => Some(Self::Flow(reading)),
-            _ => None,
+            "" => None,
+            kind => Some(Self::Other {
+                kind: kind.to_string(),
+                reading,
+            }),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[cfg(any(feature = "transducer", feature = "gill"))]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Reading {
     pub reading: Option<f32>,
     pub units: Option<UnitsOfMeasurement>,
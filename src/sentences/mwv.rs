@@ -1,11 +1,18 @@
-use super::{error::ParseNMEA0183Error, UnitsOfSpeed};
-use crate::Nmea;
+use super::{error::FieldErrorKind, UnitsOfSpeed};
+use crate::{
+    field::{FieldType, NmeaField},
+    storage::Cow,
+    tag_block::TagBlock,
+    talker::TalkerId,
+    NmeaSentence,
+};
 
 /// Represents the `$WIMWV` (Wind Instrument Mean Wind direction and Velocity) NMEA 0183 sentence.
 ///
 /// ### Fields:
 /// - `talker_id`: The talker ID of the sentence (e.g., `WI` for wind instrument).
 /// - `message_id`: The message ID, usually `MWV`.
+/// - `tag_block`: The TAG block the sentence was received with, if any.
 /// - `wind_direction_deg`: Optional integer value representing the wind direction in degrees (e.g., `049` for 49°).
 /// - `wind_dir_type`: Optional `WindDirectionType` indicating the type of wind direction measurement:
 ///   - `Relative`: Wind measurement relative to the instrument's orientation.
@@ -20,52 +27,96 @@ use crate::Nmea;
 /// - `$WIMWV,049,T,,N,A*18<CR><LF>`
 ///
 /// ### Conversion:
-/// Implements [`TryFrom<Nmea>`] to parse the `$WIMWV` sentence into an `Mwv` struct.
-/// The conversion will fail if fields cannot be parsed or contain invalid values.
-#[derive(Debug, Clone)]
+/// Implements [`TryFrom<Nmea>`](crate::Nmea) to parse the `$WIMWV` sentence into an `Mwv`
+/// struct, and `Mwv::decode` to decode it leniently.  The conversion will fail if fields cannot
+/// be parsed or contain invalid values.
+#[derive(Debug, Clone, PartialEq, NmeaSentence)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[nmea(message_id = "MWV")]
 pub struct Mwv {
-    pub talker_id: String,
-    pub message_id: String,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'static, str>,
+    pub tag_block: Option<TagBlock>,
+    #[nmea(unit = "degree")]
     pub wind_direction_deg: Option<i32>,
+    #[nmea(non_empty)]
     pub wind_dir_type: Option<WindDirectionType>,
     pub wind_speed: Option<f32>,
     pub wind_speed_units: Option<UnitsOfSpeed>,
+    #[nmea(non_empty)]
     pub acceptable: Option<AcceptableMeasurement>,
 }
 
-impl TryFrom<Nmea> for Mwv {
-    type Error = ParseNMEA0183Error;
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
-        let mwv = Mwv {
-            talker_id: nmea.talker_id,
-            message_id: nmea.message_id,
-            wind_direction_deg: nmea.fields[0].parse::<i32>().ok(),
-            wind_dir_type: Some(match nmea.fields[1].as_str() {
-                "R" => WindDirectionType::Relative,
-                "T" => WindDirectionType::True,
-                field => return Err(ParseNMEA0183Error::ConvertToEnumError(field.to_string())),
-            }),
-            wind_speed: nmea.fields[2].parse::<f32>().ok(),
-            wind_speed_units: UnitsOfSpeed::from_char(&nmea.fields[3]),
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WindDirectionType {
+    #[cfg_attr(feature = "serde", serde(rename = "R"))]
+    Relative,
+    #[cfg_attr(feature = "serde", serde(rename = "T"))]
+    True,
+}
 
-            acceptable: Some(match nmea.fields[4].as_str() {
-                "A" => AcceptableMeasurement::Acceptable,
-                "V" => AcceptableMeasurement::Void,
-                field => return Err(ParseNMEA0183Error::ConvertToEnumError(field.to_string())),
-            }),
-        };
-        Ok(mwv)
+impl WindDirectionType {
+    pub fn as_char(&self) -> char {
+        match self {
+            WindDirectionType::Relative => 'R',
+            WindDirectionType::True => 'T',
+        }
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum WindDirectionType {
-    Relative,
-    True,
+impl NmeaField for WindDirectionType {
+    const TYPE: FieldType = FieldType::Enum(&["R", "T"]);
+
+    fn decode_field(field: &str) -> Result<Self, FieldErrorKind> {
+        match field {
+            "R" => Ok(WindDirectionType::Relative),
+            "T" => Ok(WindDirectionType::True),
+            _ => Err(FieldErrorKind::InvalidEnumerator),
+        }
+    }
+
+    fn encode_field(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            WindDirectionType::Relative => "R",
+            WindDirectionType::True => "T",
+        })
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AcceptableMeasurement {
+    #[cfg_attr(feature = "serde", serde(rename = "A"))]
     Acceptable,
+    #[cfg_attr(feature = "serde", serde(rename = "V"))]
     Void,
 }
+
+impl AcceptableMeasurement {
+    pub fn as_char(&self) -> char {
+        match self {
+            AcceptableMeasurement::Acceptable => 'A',
+            AcceptableMeasurement::Void => 'V',
+        }
+    }
+}
+
+impl NmeaField for AcceptableMeasurement {
+    const TYPE: FieldType = FieldType::Enum(&["A", "V"]);
+
+    fn decode_field(field: &str) -> Result<Self, FieldErrorKind> {
+        match field {
+            "A" => Ok(AcceptableMeasurement::Acceptable),
+            "V" => Ok(AcceptableMeasurement::Void),
+            _ => Err(FieldErrorKind::InvalidEnumerator),
+        }
+    }
+
+    fn encode_field(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            AcceptableMeasurement::Acceptable => "A",
+            AcceptableMeasurement::Void => "V",
+        })
+    }
+}
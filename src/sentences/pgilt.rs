@@ -1,44 +1,145 @@
-use super::{error::ParseNMEA0183Error, Reading, TransducerReading, UnitsOfMeasurement};
-use crate::Nmea;
+use super::{
+    encode,
+    error::{FieldErrorKind, ParseNMEA0183Error},
+    field, sentence_header, DecodeContext, NmeaSentence, Reading, TransducerReading,
+    UnitsOfMeasurement,
+};
+use crate::{
+    field::{FieldMeta, FieldType, SentenceMeta},
+    storage::{format, Cow, String, ToString},
+    tag_block::TagBlock,
+    talker::TalkerId,
+    Nmea,
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Gilt {
-    pub talker_id: String,
-    pub message_id: String,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'static, str>,
+    pub tag_block: Option<TagBlock>,
     pub x_tilt: Option<TransducerReading>,
     pub y_tilt: Option<TransducerReading>,
     pub z_orientation: Option<ZOrientation>,
     pub sensor_name: Option<String>,
 }
 
-impl TryFrom<Nmea> for Gilt {
+impl TryFrom<Nmea<'_>> for Gilt {
     type Error = ParseNMEA0183Error;
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
-        Ok(Gilt {
-            talker_id: nmea.talker_id,
-            message_id: nmea.message_id,
-            x_tilt: Some(TransducerReading::AngularDisplacement(Reading {
-                reading: nmea.fields[1].parse::<f32>().ok(),
-                units: Some(UnitsOfMeasurement::Degrees),
-                name: None,
-            })),
-            y_tilt: Some(TransducerReading::AngularDisplacement(Reading {
-                reading: nmea.fields[3].parse::<f32>().ok(),
+    fn try_from(nmea: Nmea<'_>) -> Result<Self, Self::Error> {
+        Gilt::decode(nmea, &mut DecodeContext::default())
+    }
+}
+
+impl Gilt {
+    /// Decodes the sentence, `ctx` decides whether an invalid orientation rejects the sentence.
+    pub fn decode(nmea: Nmea<'_>, ctx: &mut DecodeContext) -> Result<Self, ParseNMEA0183Error> {
+        if nmea.fields.len() < 7 {
+            return Err(ParseNMEA0183Error::MissingFields(7));
+        }
+
+        let z_orientation = ctx.recover(nmea.parse_field::<i32>(5, "z_orientation").and_then(
+            |z_orientation| {
+                ZOrientation::try_from(z_orientation).map_err(|_| {
+                    nmea.field_error(5, "z_orientation", FieldErrorKind::InvalidEnumerator)
+                })
+            },
+        ))?;
+
+        let x_tilt = ctx.optional(&nmea, 1, "x_tilt.reading")?;
+        let y_tilt = ctx.optional(&nmea, 3, "y_tilt.reading")?;
+        let tilt = |reading| {
+            Some(TransducerReading::AngularDisplacement(Reading {
+                reading,
                 units: Some(UnitsOfMeasurement::Degrees),
                 name: None,
-            })),
-            z_orientation: Some(ZOrientation::try_from(nmea.fields[5].parse::<i32>()?)?),
+            }))
+        };
+
+        Ok(Gilt {
+            talker_id: nmea.talker_id,
+            message_id: Cow::Borrowed(Self::MESSAGE_ID),
+            tag_block: nmea.tag_block,
+            x_tilt: tilt(x_tilt),
+            y_tilt: tilt(y_tilt),
+            z_orientation,
             sensor_name: Some(nmea.fields[6].to_string()),
         })
     }
 }
 
+impl SentenceMeta for Gilt {
+    const MANUFACTURER: Option<&'static str> = Some("GIL");
+    const MESSAGE_ID: &'static str = "T";
+    const FIELDS: &'static [FieldMeta] = &[
+        FieldMeta::new("measurement", 0, FieldType::Enum(&["A"])).nullable(),
+        FieldMeta::new("x_tilt.reading", 1, FieldType::Float)
+            .unit("degree")
+            .nullable(),
+        FieldMeta::new("x_tilt.units", 2, FieldType::Enum(&["D"])).nullable(),
+        FieldMeta::new("y_tilt.reading", 3, FieldType::Float)
+            .unit("degree")
+            .nullable(),
+        FieldMeta::new("y_tilt.units", 4, FieldType::Enum(&["D"])).nullable(),
+        FieldMeta::new("z_orientation", 5, FieldType::Enum(&["+1", "-1"])).option(),
+        FieldMeta::new("sensor_name", 6, FieldType::Text).option(),
+    ];
+}
+
+impl NmeaSentence for Gilt {
+    sentence_header!();
+
+    fn to_nmea(&self) -> Result<Nmea<'static>, ParseNMEA0183Error> {
+        let tilt = |tilt: &Option<TransducerReading>| {
+            let reading = tilt.as_ref().map(TransducerReading::reading);
+            [
+                field(reading.and_then(|r| r.reading)),
+                field(
+                    reading
+                        .and_then(|r| r.units.as_ref())
+                        .map(UnitsOfMeasurement::as_char),
+                ),
+            ]
+        };
+        let [x, x_units] = tilt(&self.x_tilt);
+        let [y, y_units] = tilt(&self.y_tilt);
+        encode(
+            self,
+            [
+                field(Some('A')),
+                x,
+                x_units,
+                y,
+                y_units,
+                field(
+                    self.z_orientation
+                        .as_ref()
+                        .map(|z| format!("{:+}", z.as_i32())),
+                ),
+                field(self.sensor_name.as_ref()),
+            ],
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ZOrientation {
+    #[cfg_attr(feature = "serde", serde(rename = "+1"))]
     FacingUpright,
+    #[cfg_attr(feature = "serde", serde(rename = "-1"))]
     FacingDownwards,
 }
 
+impl ZOrientation {
+    pub fn as_i32(&self) -> i32 {
+        match self {
+            ZOrientation::FacingUpright => 1,
+            ZOrientation::FacingDownwards => -1,
+        }
+    }
+}
+
 impl TryFrom<i32> for ZOrientation {
     type Error = ParseNMEA0183Error;
     fn try_from(s: i32) -> Result<Self, Self::Error> {
@@ -0,0 +1,118 @@
+use super::{error::ParseNMEA0183Error, NmeaSentence};
+use crate::{
+    storage::{format, Cow, String, ToString},
+    tag_block::TagBlock,
+    talker::TalkerId,
+    Delimiter, Nmea,
+};
+use core::fmt;
+
+/// Represents a query sentence (`$ttllQ,ccc`), asking the instrument with talker ID `ll` to send
+/// sentence `ccc`.
+///
+/// ### Fields:
+/// - `requester`: The talker ID of the device asking, e.g. `CC` for a computer.
+/// - `target`: The talker ID of the device being asked, e.g. `GP`.
+/// - `message_id`: The sentence requested, e.g. `GGA`.
+/// - `tag_block`: The TAG block the sentence was received with, if any.
+///
+/// ### Example NMEA Sentences:
+/// - `$CCGPQ,GGA*2B<CR><LF>`
+///
+/// ### Conversion:
+/// Implements [`TryFrom<Nmea>`] to parse a query, which fails with
+/// [`ParseNMEA0183Error::UnknownSentence`] for sentences that are not queries, and
+/// [`fmt::Display`] to write one.  As a
+/// [`NmeaSentence`] its talker ID is the requester and its message ID the target followed by
+/// `Q`, as in the sentence.
+///
+/// ```
+/// use oor_nmea0183::{sentences::query::Query, talker::TalkerId, NmeaParser, Sentence};
+///
+/// let query = Query::new(TalkerId::Computer, TalkerId::Gps, "GGA");
+/// assert_eq!(query.to_string(), "$CCGPQ,GGA*2B");
+/// assert!(matches!(NmeaParser::parse("$CCGPQ,GGA*2B"), Ok(Sentence::Query(q)) if q == query));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Query {
+    pub requester: TalkerId,
+    pub target: TalkerId,
+    pub message_id: String,
+    pub tag_block: Option<TagBlock>,
+}
+
+impl Query {
+    pub fn new(requester: TalkerId, target: TalkerId, message_id: &str) -> Self {
+        Query {
+            requester,
+            target,
+            message_id: message_id.to_string(),
+            tag_block: None,
+        }
+    }
+
+    /// Whether `nmea` is a query, i.e. its message ID is a talker ID followed by `Q` and its only
+    /// field the three letters of the sentence requested.
+    pub fn is_query(nmea: &Nmea<'_>) -> bool {
+        let letters = |s: &str| s.bytes().all(|b| b.is_ascii_uppercase());
+        !nmea.talker_id.is_proprietary()
+            && nmea.message_id.len() == 3
+            && nmea
+                .message_id
+                .strip_suffix('Q')
+                .is_some_and(|target| target.parse::<TalkerId>().is_ok())
+            && matches!(&nmea.fields[..], [field] if field.len() == 3 && letters(field))
+    }
+}
+
+impl NmeaSentence for Query {
+    fn talker_id(&self) -> &TalkerId {
+        &self.requester
+    }
+
+    fn message_id(&self) -> Cow<'_, str> {
+        Cow::Owned(format!("{}Q", self.target))
+    }
+
+    fn tag_block(&self) -> Option<&TagBlock> {
+        self.tag_block.as_ref()
+    }
+
+    fn to_nmea(&self) -> Result<Nmea<'static>, ParseNMEA0183Error> {
+        Ok(Nmea {
+            tag_block: self.tag_block.clone(),
+            delimiter: Delimiter::Parametric,
+            talker_id: self.requester.clone(),
+            message_id: Cow::Owned(self.message_id().into_owned()),
+            fields: [Cow::Owned(self.message_id.clone())].into_iter().collect(),
+        })
+    }
+}
+
+impl TryFrom<Nmea<'_>> for Query {
+    type Error = ParseNMEA0183Error;
+    fn try_from(nmea: Nmea<'_>) -> Result<Self, Self::Error> {
+        if !Query::is_query(&nmea) {
+            return Err(ParseNMEA0183Error::UnknownSentence(format!(
+                "{}{}",
+                nmea.talker_id.manufacturer().unwrap_or_default(),
+                nmea.message_id
+            )));
+        }
+        Ok(Query {
+            requester: nmea.talker_id,
+            target: nmea.message_id[..2].parse()?,
+            message_id: nmea.fields[0].to_string(),
+            tag_block: nmea.tag_block,
+        })
+    }
+}
+
+/// Writes the query as a sentence with a checksum, without the line ending.
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // A query has a single field, which always fits.
+        write!(f, "{}", self.to_nmea().map_err(|_| fmt::Error)?)
+    }
+}
@@ -6,3 +6,35 @@ pub mod psvss;
 pub mod psvst;
 pub mod psvsv;
 pub mod psvsw;
+
+/// Serializes `uom` quantities as numbers in the unit of their field rather than in SI base
+/// units, for the quantities whose field unit is not the base unit.
+#[cfg(feature = "serde")]
+pub(crate) mod serde_unit {
+    macro_rules! in_unit {
+        ($kind:ident, $unit:ident, $quantity:ident) => {
+            pub(crate) mod $unit {
+                use serde::{Deserialize, Deserializer, Serialize, Serializer};
+                use uom::si::{f32::$quantity, $kind::$unit};
+
+                pub(crate) fn serialize<S: Serializer>(
+                    value: &Option<$quantity>,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    value
+                        .map(|value| value.get::<$unit>())
+                        .serialize(serializer)
+                }
+
+                pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<Option<$quantity>, D::Error> {
+                    Ok(Option::<f32>::deserialize(deserializer)?.map($quantity::new::<$unit>))
+                }
+            }
+        };
+    }
+
+    in_unit!(angle, degree, Angle);
+    in_unit!(angular_velocity, degree_per_second, AngularVelocity);
+}
@@ -1,4 +1,4 @@
-use crate::{sentences::error::ParseNMEA0183Error, Nmea};
+use crate::{storage::Cow, tag_block::TagBlock, talker::TalkerId, NmeaSentence};
 use uom::si::{
     acceleration::meter_per_second_squared,
     angle::degree,
@@ -16,108 +16,69 @@ use uom::si::{
 /// ```text
 /// $PSVDY,accX,accY,accZ,gyrp,gyrq,gyrr,angH,angP,angR,accN,accE,accU,index*CS
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, NmeaSentence)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[nmea(manufacturer = "SVD", message_id = "Y")]
 pub struct Svdy {
-    pub talker_id: String,
-    pub message_id: String,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'static, str>,
+    pub tag_block: Option<TagBlock>,
 
     /// X-axis acceleration in the **sensor frame** (m/s²).
+    #[nmea(unit = meter_per_second_squared)]
     pub acc_x: Option<Acceleration>,
     /// Y-axis acceleration in the **sensor frame** (m/s²).
+    #[nmea(unit = meter_per_second_squared)]
     pub acc_y: Option<Acceleration>,
     /// Z-axis acceleration in the **sensor frame** (m/s²).
+    #[nmea(unit = meter_per_second_squared)]
     pub acc_z: Option<Acceleration>,
 
     /// Angular rate about the **X-axis** (deg/s).
+    #[nmea(unit = degree_per_second)]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "super::serde_unit::degree_per_second")
+    )]
     pub gyr_p: Option<AngularVelocity>,
     /// Angular rate about the **Y-axis** (deg/s).
+    #[nmea(unit = degree_per_second)]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "super::serde_unit::degree_per_second")
+    )]
     pub gyr_q: Option<AngularVelocity>,
     /// Angular rate about the **Z-axis** (deg/s).
+    #[nmea(unit = degree_per_second)]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "super::serde_unit::degree_per_second")
+    )]
     pub gyr_r: Option<AngularVelocity>,
 
     /// Heading angle (deg).
+    #[nmea(unit = degree)]
+    #[cfg_attr(feature = "serde", serde(with = "super::serde_unit::degree"))]
     pub heading: Option<Angle>,
     /// Pitch angle (deg).
+    #[nmea(unit = degree)]
+    #[cfg_attr(feature = "serde", serde(with = "super::serde_unit::degree"))]
     pub pitch: Option<Angle>,
     /// Roll angle (deg).
+    #[nmea(unit = degree)]
+    #[cfg_attr(feature = "serde", serde(with = "super::serde_unit::degree"))]
     pub roll: Option<Angle>,
 
     /// Acceleration resolved in the **North** direction (m/s²).
+    #[nmea(unit = meter_per_second_squared)]
     pub acc_n: Option<Acceleration>,
     /// Acceleration resolved in the **East** direction (m/s²).
+    #[nmea(unit = meter_per_second_squared)]
     pub acc_e: Option<Acceleration>,
     /// Acceleration resolved in the **Up** direction (m/s²).
+    #[nmea(unit = meter_per_second_squared)]
     pub acc_u: Option<Acceleration>,
 
     /// Monotonic sample index for detecting gaps and ordering.
     pub index: Option<u32>,
 }
-
-impl TryFrom<Nmea> for Svdy {
-    type Error = ParseNMEA0183Error;
-
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
-        if nmea.fields.len() < 12 {
-            return Err(ParseNMEA0183Error::MissingFields(12));
-        }
-
-        Ok(Svdy {
-            talker_id: nmea.talker_id,
-            message_id: nmea.message_id,
-
-            acc_x: nmea.fields[0]
-                .parse::<f32>()
-                .ok()
-                .map(|v| Acceleration::new::<meter_per_second_squared>(v)),
-            acc_y: nmea.fields[1]
-                .parse::<f32>()
-                .ok()
-                .map(|v| Acceleration::new::<meter_per_second_squared>(v)),
-            acc_z: nmea.fields[2]
-                .parse::<f32>()
-                .ok()
-                .map(|v| Acceleration::new::<meter_per_second_squared>(v)),
-
-            gyr_p: nmea.fields[3]
-                .parse::<f32>()
-                .ok()
-                .map(|v| AngularVelocity::new::<degree_per_second>(v)),
-            gyr_q: nmea.fields[4]
-                .parse::<f32>()
-                .ok()
-                .map(|v| AngularVelocity::new::<degree_per_second>(v)),
-            gyr_r: nmea.fields[5]
-                .parse::<f32>()
-                .ok()
-                .map(|v| AngularVelocity::new::<degree_per_second>(v)),
-
-            heading: nmea.fields[6]
-                .parse::<f32>()
-                .ok()
-                .map(|v| Angle::new::<degree>(v)),
-            pitch: nmea.fields[7]
-                .parse::<f32>()
-                .ok()
-                .map(|v| Angle::new::<degree>(v)),
-            roll: nmea.fields[8]
-                .parse::<f32>()
-                .ok()
-                .map(|v| Angle::new::<degree>(v)),
-
-            acc_n: nmea.fields[9]
-                .parse::<f32>()
-                .ok()
-                .map(|v| Acceleration::new::<meter_per_second_squared>(v)),
-            acc_e: nmea.fields[10]
-                .parse::<f32>()
-                .ok()
-                .map(|v| Acceleration::new::<meter_per_second_squared>(v)),
-            acc_u: nmea.fields[11]
-                .parse::<f32>()
-                .ok()
-                .map(|v| Acceleration::new::<meter_per_second_squared>(v)),
-
-            index: nmea.fields[12].parse::<u32>().ok(),
-        })
-    }
-}
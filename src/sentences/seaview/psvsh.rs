@@ -1,4 +1,9 @@
-use crate::{sentences::error::ParseNMEA0183Error, Nmea};
+use crate::{
+    storage::{Cow, String, Vec},
+    tag_block::TagBlock,
+    talker::TalkerId,
+    NmeaSentence,
+};
 
 /// Nmea header messages (`$PSVSH`) from the SVS-603HR.
 ///
@@ -6,22 +11,14 @@ use crate::{sentences::error::ParseNMEA0183Error, Nmea};
 /// ```text
 /// $PSVSH,Period,ESmag,Nre,Nim,Ere,Eim,Ure,Uim,Umag,theta,a1,b1,a2,b2*57
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, NmeaSentence)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[nmea(manufacturer = "SVS", message_id = "H")]
 pub struct Svsh {
-    pub talker_id: String,
-    pub message_id: String,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'static, str>,
+    pub tag_block: Option<TagBlock>,
 
+    #[nmea(repeated, optional)]
     pub headers: Vec<String>,
 }
-
-impl TryFrom<Nmea> for Svsh {
-    type Error = ParseNMEA0183Error;
-
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
-        Ok(Svsh {
-            talker_id: nmea.talker_id,
-            message_id: nmea.message_id,
-            headers: nmea.fields,
-        })
-    }
-}
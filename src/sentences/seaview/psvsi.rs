@@ -1,4 +1,9 @@
-use crate::{sentences::error::ParseNMEA0183Error, Nmea};
+use crate::{
+    storage::{Cow, String},
+    tag_block::TagBlock,
+    talker::TalkerId,
+    NmeaSentence,
+};
 
 /// Identity report (`$PSVSI`) from the SVS-603HR.
 ///
@@ -6,27 +11,14 @@ use crate::{sentences::error::ParseNMEA0183Error, Nmea};
 /// ```text
 /// $PSVSI,WINDMILL*73
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, NmeaSentence)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[nmea(manufacturer = "SVS", message_id = "I")]
 pub struct Svsi {
-    pub talker_id: String,
-    pub message_id: String,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'static, str>,
+    pub tag_block: Option<TagBlock>,
 
     /// Identity of the Seaview SVS-603HR device
     pub identity: String,
 }
-
-impl TryFrom<Nmea> for Svsi {
-    type Error = ParseNMEA0183Error;
-
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
-        if nmea.fields.is_empty() {
-            return Err(ParseNMEA0183Error::MissingFields(1));
-        }
-
-        Ok(Svsi {
-            talker_id: nmea.talker_id,
-            message_id: nmea.message_id,
-            identity: nmea.fields[0].clone(),
-        })
-    }
-}
@@ -1,7 +1,15 @@
-use crate::{sentences::error::ParseNMEA0183Error, Nmea};
+use crate::{
+    field::{FieldMeta, FieldType, SentenceMeta},
+    sentences::{encode, error::ParseNMEA0183Error, field, sentence_header, NmeaSentence},
+    storage::Cow,
+    tag_block::TagBlock,
+    talker::TalkerId,
+    Nmea,
+};
 
 /// A single frequency-bin entry from the wave energy spectrum.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpectrumBin {
     pub period: f32, // Period for the bin
     pub es_mag: f32, // Energy spectrum magnitude
@@ -30,44 +38,84 @@ pub struct SpectrumBin {
 /// ```text
 /// $PSVSS,Period,ESmag,Nre,Nim,Ere,Eim,Ure,Uim,Umag,theta,a1,b1,a2,b2*CS
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Svss {
-    pub talker_id: String,
-    pub message_id: String,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'static, str>,
+    pub tag_block: Option<TagBlock>,
 
     /// The spectrum data for this frequency bin.
     pub bin: SpectrumBin,
 }
 
-impl TryFrom<Nmea> for Svss {
+impl TryFrom<Nmea<'_>> for Svss {
     type Error = ParseNMEA0183Error;
 
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
+    fn try_from(nmea: Nmea<'_>) -> Result<Self, Self::Error> {
         if nmea.fields.len() < 14 {
             return Err(ParseNMEA0183Error::MissingFields(14));
         }
 
         let bin = SpectrumBin {
-            period: nmea.fields[0].parse::<f32>()?,
-            es_mag: nmea.fields[1].parse::<f32>()?,
-            n_re: nmea.fields[2].parse::<f32>()?,
-            n_im: nmea.fields[3].parse::<f32>()?,
-            e_re: nmea.fields[4].parse::<f32>()?,
-            e_im: nmea.fields[5].parse::<f32>()?,
-            u_re: nmea.fields[6].parse::<f32>()?,
-            u_im: nmea.fields[7].parse::<f32>()?,
-            u_mag: nmea.fields[8].parse::<f32>()?,
-            theta: nmea.fields[9].parse::<f32>()?,
-            a1: nmea.fields[10].parse::<f32>()?,
-            b1: nmea.fields[11].parse::<f32>()?,
-            a2: nmea.fields[12].parse::<f32>()?,
-            b2: nmea.fields[13].parse::<f32>()?,
+            period: nmea.parse_field::<f32>(0, "period")?,
+            es_mag: nmea.parse_field::<f32>(1, "es_mag")?,
+            n_re: nmea.parse_field::<f32>(2, "n_re")?,
+            n_im: nmea.parse_field::<f32>(3, "n_im")?,
+            e_re: nmea.parse_field::<f32>(4, "e_re")?,
+            e_im: nmea.parse_field::<f32>(5, "e_im")?,
+            u_re: nmea.parse_field::<f32>(6, "u_re")?,
+            u_im: nmea.parse_field::<f32>(7, "u_im")?,
+            u_mag: nmea.parse_field::<f32>(8, "u_mag")?,
+            theta: nmea.parse_field::<f32>(9, "theta")?,
+            a1: nmea.parse_field::<f32>(10, "a1")?,
+            b1: nmea.parse_field::<f32>(11, "b1")?,
+            a2: nmea.parse_field::<f32>(12, "a2")?,
+            b2: nmea.parse_field::<f32>(13, "b2")?,
         };
 
         Ok(Svss {
             talker_id: nmea.talker_id,
-            message_id: nmea.message_id,
+            message_id: Cow::Borrowed(Self::MESSAGE_ID),
+            tag_block: nmea.tag_block,
             bin,
         })
     }
 }
+
+impl SentenceMeta for Svss {
+    const MANUFACTURER: Option<&'static str> = Some("SVS");
+    const MESSAGE_ID: &'static str = "S";
+    const FIELDS: &'static [FieldMeta] = &[
+        FieldMeta::new("bin.period", 0, FieldType::Float).unit("second"),
+        FieldMeta::new("bin.es_mag", 1, FieldType::Float),
+        FieldMeta::new("bin.n_re", 2, FieldType::Float),
+        FieldMeta::new("bin.n_im", 3, FieldType::Float),
+        FieldMeta::new("bin.e_re", 4, FieldType::Float),
+        FieldMeta::new("bin.e_im", 5, FieldType::Float),
+        FieldMeta::new("bin.u_re", 6, FieldType::Float),
+        FieldMeta::new("bin.u_im", 7, FieldType::Float),
+        FieldMeta::new("bin.u_mag", 8, FieldType::Float),
+        FieldMeta::new("bin.theta", 9, FieldType::Float).unit("radian"),
+        FieldMeta::new("bin.a1", 10, FieldType::Float),
+        FieldMeta::new("bin.b1", 11, FieldType::Float),
+        FieldMeta::new("bin.a2", 12, FieldType::Float),
+        FieldMeta::new("bin.b2", 13, FieldType::Float),
+    ];
+}
+
+impl NmeaSentence for Svss {
+    sentence_header!();
+
+    fn to_nmea(&self) -> Result<Nmea<'static>, ParseNMEA0183Error> {
+        let bin = &self.bin;
+        encode(
+            self,
+            [
+                bin.period, bin.es_mag, bin.n_re, bin.n_im, bin.e_re, bin.e_im, bin.u_re, bin.u_im,
+                bin.u_mag, bin.theta, bin.a1, bin.b1, bin.a2, bin.b2,
+            ]
+            .map(|value| field(Some(value))),
+        )
+    }
+}
@@ -1,5 +1,16 @@
-use crate::{sentences::error::ParseNMEA0183Error, Nmea};
-use chrono::NaiveDateTime;
+use crate::{
+    field::{FieldMeta, FieldType, SentenceMeta},
+    sentences::{
+        encode,
+        error::{FieldErrorKind, ParseNMEA0183Error},
+        field, sentence_header, DecodeContext, NmeaSentence,
+    },
+    storage::{format, Cow},
+    tag_block::TagBlock,
+    talker::TalkerId,
+    Nmea,
+};
+use chrono::{Datelike, NaiveDateTime, Timelike};
 
 /// Displacement time series timestamp (`$PSVST`) from the SVS-603HR.
 ///
@@ -11,29 +22,76 @@ use chrono::NaiveDateTime;
 /// ```text
 /// $PSVST,YYYY-MM-DD HH:MM:SS*CS
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Svst {
-    pub talker_id: String,
-    pub message_id: String,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'static, str>,
+    pub tag_block: Option<TagBlock>,
 
     /// UTC timestamp parsed from the sentence.
     pub timestamp: Option<NaiveDateTime>,
 }
 
-impl TryFrom<Nmea> for Svst {
+impl TryFrom<Nmea<'_>> for Svst {
     type Error = ParseNMEA0183Error;
 
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
+    fn try_from(nmea: Nmea<'_>) -> Result<Self, Self::Error> {
+        Svst::decode(nmea, &mut DecodeContext::default())
+    }
+}
+
+impl Svst {
+    /// Decodes the sentence, `ctx` decides whether an invalid timestamp rejects the sentence.
+    pub fn decode(nmea: Nmea<'_>, ctx: &mut DecodeContext) -> Result<Self, ParseNMEA0183Error> {
         if nmea.fields.is_empty() {
             return Err(ParseNMEA0183Error::MissingFields(1));
         }
 
-        let timestamp = NaiveDateTime::parse_from_str(&nmea.fields[0], "%Y-%m-%d %H:%M:%S").ok();
+        let timestamp = match nmea.fields[0].as_ref() {
+            "" => None,
+            timestamp => ctx.recover(
+                NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+                    .map_err(|_| nmea.field_error(0, "timestamp", FieldErrorKind::InvalidFormat)),
+            )?,
+        };
 
         Ok(Svst {
             talker_id: nmea.talker_id,
-            message_id: nmea.message_id,
+            message_id: Cow::Borrowed(Self::MESSAGE_ID),
+            tag_block: nmea.tag_block,
             timestamp,
         })
     }
 }
+
+impl SentenceMeta for Svst {
+    const MANUFACTURER: Option<&'static str> = Some("SVS");
+    const MESSAGE_ID: &'static str = "T";
+    const FIELDS: &'static [FieldMeta] =
+        &[FieldMeta::new("timestamp", 0, FieldType::Text).nullable()];
+}
+
+impl NmeaSentence for Svst {
+    sentence_header!();
+
+    fn timestamp(&self) -> Option<NaiveDateTime> {
+        self.timestamp
+            .or_else(|| self.tag_block.as_ref()?.timestamp())
+    }
+
+    fn to_nmea(&self) -> Result<Nmea<'static>, ParseNMEA0183Error> {
+        let timestamp = self.timestamp.map(|t| {
+            format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                t.year(),
+                t.month(),
+                t.day(),
+                t.hour(),
+                t.minute(),
+                t.second()
+            )
+        });
+        encode(self, [field(timestamp)])
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{sentences::error::ParseNMEA0183Error, Nmea};
+use crate::{storage::Cow, tag_block::TagBlock, talker::TalkerId, NmeaSentence};
 use uom::si::{
     f32::{Length, Time},
     length::meter,
@@ -11,44 +11,20 @@ use uom::si::{
 /// ```text
 /// $PSVSV,0.000,0.024,0.037,-0.663*7C
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, NmeaSentence)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[nmea(manufacturer = "SVS", message_id = "V")]
 pub struct Svsv {
-    pub talker_id: String,
-    pub message_id: String,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'static, str>,
+    pub tag_block: Option<TagBlock>,
 
+    #[nmea(unit = second)]
     pub time: Option<Time>,
+    #[nmea(unit = meter)]
     pub north: Option<Length>,
+    #[nmea(unit = meter)]
     pub east: Option<Length>,
+    #[nmea(unit = meter)]
     pub up: Option<Length>,
 }
-
-impl TryFrom<Nmea> for Svsv {
-    type Error = ParseNMEA0183Error;
-
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
-        if nmea.fields.len() < 4 {
-            return Err(ParseNMEA0183Error::MissingFields(4));
-        }
-
-        Ok(Svsv {
-            talker_id: nmea.talker_id,
-            message_id: nmea.message_id,
-            time: nmea.fields[0]
-                .parse::<f32>()
-                .ok()
-                .map(|v| Time::new::<second>(v)),
-            north: nmea.fields[1]
-                .parse::<f32>()
-                .ok()
-                .map(|v| Length::new::<meter>(v)),
-            east: nmea.fields[2]
-                .parse::<f32>()
-                .ok()
-                .map(|v| Length::new::<meter>(v)),
-            up: nmea.fields[3]
-                .parse::<f32>()
-                .ok()
-                .map(|v| Length::new::<meter>(v)),
-        })
-    }
-}
@@ -1,4 +1,9 @@
-use crate::{sentences::error::ParseNMEA0183Error, Nmea};
+use crate::{
+    storage::{Cow, String, Vec},
+    tag_block::TagBlock,
+    talker::TalkerId,
+    NmeaSentence,
+};
 
 /// Summary message (`$PSVSW`) from the SVS-603HR.
 ///
@@ -11,22 +16,14 @@ use crate::{sentences::error::ParseNMEA0183Error, Nmea};
 /// ```text
 /// $PSVSW,0.570,2.560,311.141,311.142,3.872,1.559,0.118,3.824,12.6,23.8,SVS-603HR,2023-01-12 12:26:27,255*1A
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, NmeaSentence)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[nmea(manufacturer = "SVS", message_id = "W")]
 pub struct Svsw {
-    pub talker_id: String,
-    pub message_id: String,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'static, str>,
+    pub tag_block: Option<TagBlock>,
 
+    #[nmea(repeated, optional)]
     pub params: Vec<String>,
 }
-
-impl TryFrom<Nmea> for Svsw {
-    type Error = ParseNMEA0183Error;
-
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
-        Ok(Svsw {
-            talker_id: nmea.talker_id,
-            message_id: nmea.message_id,
-            params: nmea.fields,
-        })
-    }
-}
@@ -1,11 +1,21 @@
-use super::{error::ParseNMEA0183Error, Reading, TransducerReading, UnitsOfMeasurement};
-use crate::Nmea;
+use super::{
+    encode, error::ParseNMEA0183Error, field, sentence_header, DecodeContext, NmeaSentence,
+    Reading, TransducerReading, UnitsOfMeasurement,
+};
+use crate::{
+    field::{FieldMeta, FieldType, SentenceMeta},
+    storage::{Cow, ToString, TryPush, Vec},
+    tag_block::TagBlock,
+    talker::TalkerId,
+    Nmea,
+};
 
 /// Represents the `$WIXDR` (Transducer Measurements) NMEA 0183 sentence.
 ///
 /// ### Fields:
 /// - `talker_id`: The talker ID of the sentence (e.g., `WI` for wind instrument).
 /// - `message_id`: The message ID, typically `XDR`.
+/// - `tag_block`: The TAG block the sentence was received with, if any.
 /// - `readings`: A vector of optional `TransducerReading` values. Each reading includes:
 ///   - Measurement value (`reading`) as a floating-point number.
 ///   - Units of measurement (`units`), parsed from a single character.
@@ -18,38 +28,119 @@ use crate::Nmea;
 /// ### Conversion:
 /// Implements [`TryFrom<Nmea>`] to parse the `$WIXDR` sentence into an `Xdr` struct.
 /// The conversion uses the `to_readings` helper function to process the fields and extract transducer readings.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Xdr {
-    pub talker_id: String,
-    pub message_id: String,
+    pub talker_id: TalkerId,
+    pub message_id: Cow<'static, str>,
+    pub tag_block: Option<TagBlock>,
     pub readings: Vec<Option<TransducerReading>>,
 }
 
-impl TryFrom<Nmea> for Xdr {
+impl TryFrom<Nmea<'_>> for Xdr {
     type Error = ParseNMEA0183Error;
-    fn try_from(nmea: Nmea) -> Result<Self, Self::Error> {
+    fn try_from(nmea: Nmea<'_>) -> Result<Self, Self::Error> {
+        Xdr::decode(nmea, &mut DecodeContext::default())
+    }
+}
+
+impl Xdr {
+    /// Decodes the sentence, `ctx` decides whether an invalid value or unit rejects the
+    /// sentence.
+    pub fn decode(nmea: Nmea<'_>, ctx: &mut DecodeContext) -> Result<Self, ParseNMEA0183Error> {
+        // Every reading is a quadruple of type, value, units and name.
+        if !nmea.fields.len().is_multiple_of(4) {
+            return Err(ParseNMEA0183Error::MissingFields(
+                nmea.fields.len().next_multiple_of(4) as u16,
+            ));
+        }
+
+        let mut readings = Vec::new();
+        for (i, quadruple) in nmea.fields.chunks_exact(4).enumerate() {
+            let reading = Reading {
+                reading: ctx.optional(&nmea, i * 4 + 1, "readings.reading")?,
+                units: ctx.optional(&nmea, i * 4 + 2, "readings.units")?,
+                name: Some(quadruple[3].to_string()),
+            };
+            readings.try_push(TransducerReading::from_nmea(&quadruple[0], reading))?;
+        }
+
         Ok(Xdr {
-            talker_id: nmea.talker_id.clone(),
-            message_id: nmea.message_id.clone(),
-            readings: to_readings(nmea.fields),
+            readings,
+            talker_id: nmea.talker_id,
+            message_id: Cow::Borrowed(Self::MESSAGE_ID),
+            tag_block: nmea.tag_block,
         })
     }
 }
 
-pub fn to_readings(fields: Vec<String>) -> Vec<Option<TransducerReading>> {
-    let mut readings: Vec<Option<TransducerReading>> = vec![];
-    for i in (0..fields.len()).step_by(4) {
+/// Readings without a transducer type are written as four empty fields.  Any other type is
+/// accepted, the ones without a [`TransducerReading`] variant are kept as
+/// [`TransducerReading::Other`].
+impl SentenceMeta for Xdr {
+    const MANUFACTURER: Option<&'static str> = None;
+    const MESSAGE_ID: &'static str = "XDR";
+    const FIELDS: &'static [FieldMeta] = &[
+        FieldMeta::new("readings.type", 0, FieldType::Text)
+            .nullable()
+            .repeated(),
+        FieldMeta::new("readings.reading", 1, FieldType::Float)
+            .nullable()
+            .repeated(),
+        FieldMeta::new(
+            "readings.units",
+            2,
+            FieldType::Enum(&["A", "B", "C", "D", "H", "I", "K", "M", "N", "P", "S", "V"]),
+        )
+        .nullable()
+        .repeated(),
+        FieldMeta::new("readings.name", 3, FieldType::Text)
+            .nullable()
+            .repeated(),
+    ];
+}
+
+impl NmeaSentence for Xdr {
+    sentence_header!();
+
+    fn to_nmea(&self) -> Result<Nmea<'static>, ParseNMEA0183Error> {
+        encode(
+            self,
+            self.readings.iter().flat_map(|transducer| {
+                let reading = transducer.as_ref().map(TransducerReading::reading);
+                [
+                    field(transducer.as_ref().map(TransducerReading::kind)),
+                    field(reading.and_then(|r| r.reading)),
+                    field(
+                        reading
+                            .and_then(|r| r.units.as_ref())
+                            .map(UnitsOfMeasurement::as_char),
+                    ),
+                    field(reading.and_then(|r| r.name.as_ref())),
+                ]
+            }),
+        )
+    }
+}
+
+/// The readings of the quadruples in `fields`, fails with
+/// [`ParseNMEA0183Error::CapacityExceeded`] if there are more than fit into a [`Vec`].
+pub fn to_readings<S: AsRef<str>>(
+    fields: &[S],
+) -> Result<Vec<Option<TransducerReading>>, ParseNMEA0183Error> {
+    let mut readings: Vec<Option<TransducerReading>> = Vec::new();
+    for quadruple in fields.chunks_exact(4) {
         let reading = Reading {
-            reading: fields[i + 1].parse::<f32>().ok(),
-            units: UnitsOfMeasurement::from_char(&fields[i + 2]),
-            name: Some(fields[i + 3].clone()),
+            reading: quadruple[1].as_ref().parse::<f32>().ok(),
+            units: UnitsOfMeasurement::from_char(quadruple[2].as_ref()),
+            name: Some(quadruple[3].as_ref().to_string()),
         };
-        readings.push(TransducerReading::from_nmea(&fields[i], reading));
+        readings.try_push(TransducerReading::from_nmea(quadruple[0].as_ref(), reading))?;
     }
-    readings
+    Ok(readings)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
@@ -58,9 +149,42 @@ mod tests {
         let fields = vec![
             "C", "+023.9", "C", "TEMP", "P", "1.0243", "B", "PRESS", "H", "039", "P", "RH",
         ];
-        let fields = fields.into_iter().map(|v| v.to_string()).collect();
-        let readings = to_readings(fields);
+        let readings = to_readings(&fields).unwrap();
 
         assert_eq!(readings.len(), 3);
     }
+
+    #[test]
+    fn test_to_readings_incomplete_quadruple() {
+        let fields = vec!["C", "+023.9", "C", "TEMP", "P", "1.0243"];
+        let readings = to_readings(&fields).unwrap();
+
+        assert_eq!(readings.len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_transducer_type() {
+        let line = "$WIXDR,G,12.0,,GEN,C,+023.1,C,TEMP,,,,";
+        let xdr = Xdr::try_from(crate::NmeaParser::to_nmea(line).unwrap()).unwrap();
+        assert_eq!(
+            xdr.readings[0],
+            Some(TransducerReading::Other {
+                kind: "G".to_string(),
+                reading: Reading {
+                    reading: Some(12.0),
+                    units: None,
+                    name: Some("GEN".to_string()),
+                },
+            })
+        );
+        assert!(matches!(
+            xdr.readings[1],
+            Some(TransducerReading::Temperature(_))
+        ));
+        assert_eq!(xdr.readings[2], None);
+        assert_eq!(
+            xdr.to_nmea().unwrap().to_string(),
+            "$WIXDR,G,12,,GEN,C,23.1,C,TEMP,,,,*4A"
+        );
+    }
 }
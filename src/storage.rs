@@ -0,0 +1,335 @@
+use crate::sentences::error::ParseNMEA0183Error;
+
+/// Longest string kept without an allocator, the maximum length of an NMEA0183 sentence.
+/// Longer values are truncated.
+pub const STRING_CAPACITY: usize = 82;
+
+/// Most fields of a sentence kept without an allocator.
+pub const VEC_CAPACITY: usize = 32;
+
+/// Most entries of the shorter lists kept without an allocator, e.g. the filters of a
+/// [`crate::config::ParserConfig`] or the warnings of a lenient decode.
+pub const LIST_CAPACITY: usize = 8;
+
+/// Bytes a [`crate::framer::Framer`] buffers without an allocator.
+pub const BUFFER_CAPACITY: usize = 1024;
+
+#[cfg(feature = "alloc")]
+pub use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
+
+#[cfg(feature = "alloc")]
+pub(crate) use alloc::{format, string::ToString};
+
+/// A short list, see [`LIST_CAPACITY`].
+#[cfg(feature = "alloc")]
+pub type List<T> = Vec<T>;
+
+#[cfg(feature = "alloc")]
+pub(crate) type Bytes = Vec<u8>;
+
+#[cfg(feature = "alloc")]
+pub(crate) type Buffer = Vec<u8>;
+
+#[cfg(feature = "alloc")]
+#[allow(clippy::boxed_local)] // The stand-in without an allocator takes the box by value too.
+pub(crate) fn unbox<T>(boxed: Box<T>) -> T {
+    *boxed
+}
+
+#[cfg(not(feature = "alloc"))]
+pub use fixed::{Box, Cow, List, String, Vec};
+
+#[cfg(not(feature = "alloc"))]
+pub(crate) use fixed::{format, unbox, Buffer, Bytes, ToString, Truncate};
+
+/// Appending to the storage types, which fails instead of growing when there is no allocator.
+pub(crate) trait TryPush<T> {
+    fn try_push(&mut self, value: T) -> Result<(), ParseNMEA0183Error>;
+
+    fn try_extend_from_slice(&mut self, values: &[T]) -> Result<(), ParseNMEA0183Error>
+    where
+        T: Clone;
+}
+
+#[cfg(feature = "alloc")]
+impl<T> TryPush<T> for Vec<T> {
+    fn try_push(&mut self, value: T) -> Result<(), ParseNMEA0183Error> {
+        self.push(value);
+        Ok(())
+    }
+
+    fn try_extend_from_slice(&mut self, values: &[T]) -> Result<(), ParseNMEA0183Error>
+    where
+        T: Clone,
+    {
+        self.extend_from_slice(values);
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl<T, const N: usize> TryPush<T> for heapless::Vec<T, N> {
+    fn try_push(&mut self, value: T) -> Result<(), ParseNMEA0183Error> {
+        self.push(value)
+            .map_err(|_| ParseNMEA0183Error::CapacityExceeded(N))
+    }
+
+    fn try_extend_from_slice(&mut self, values: &[T]) -> Result<(), ParseNMEA0183Error>
+    where
+        T: Clone,
+    {
+        self.extend_from_slice(values)
+            .map_err(|_| ParseNMEA0183Error::CapacityExceeded(N))
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+mod fixed {
+    use super::{BUFFER_CAPACITY, LIST_CAPACITY, STRING_CAPACITY, VEC_CAPACITY};
+    use core::{
+        fmt::{self, Write},
+        ops::{Deref, DerefMut},
+    };
+
+    pub type String = heapless::String<STRING_CAPACITY>;
+    pub type Vec<T> = heapless::Vec<T, VEC_CAPACITY>;
+    pub type List<T> = heapless::Vec<T, LIST_CAPACITY>;
+    pub(crate) type Bytes = heapless::Vec<u8, STRING_CAPACITY>;
+    pub(crate) type Buffer = heapless::Vec<u8, BUFFER_CAPACITY>;
+
+    /// Stand-in for `alloc::boxed::Box` that keeps the value inline.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Box<T>(T);
+
+    impl<T> Box<T> {
+        pub fn new(value: T) -> Self {
+            Box(value)
+        }
+    }
+
+    impl<T> Deref for Box<T> {
+        type Target = T;
+        fn deref(&self) -> &T {
+            &self.0
+        }
+    }
+
+    impl<T> DerefMut for Box<T> {
+        fn deref_mut(&mut self) -> &mut T {
+            &mut self.0
+        }
+    }
+
+    impl<T: fmt::Display> fmt::Display for Box<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+
+    pub(crate) fn unbox<T>(boxed: Box<T>) -> T {
+        boxed.0
+    }
+
+    /// Stand-in for `alloc::borrow::Cow<str>` with a fixed-capacity owned string.
+    pub enum Cow<'a, B: ?Sized + 'a> {
+        Borrowed(&'a B),
+        Owned(String),
+    }
+
+    impl Clone for Cow<'_, str> {
+        fn clone(&self) -> Self {
+            match self {
+                Cow::Borrowed(s) => Cow::Borrowed(s),
+                Cow::Owned(s) => Cow::Owned(s.clone()),
+            }
+        }
+    }
+
+    impl fmt::Debug for Cow<'_, str> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Debug::fmt(&**self, f)
+        }
+    }
+
+    impl PartialEq for Cow<'_, str> {
+        fn eq(&self, other: &Self) -> bool {
+            **self == **other
+        }
+    }
+
+    impl Eq for Cow<'_, str> {}
+
+    impl Cow<'_, str> {
+        pub fn into_owned(self) -> String {
+            match self {
+                Cow::Borrowed(s) => s.to_string(),
+                Cow::Owned(s) => s,
+            }
+        }
+    }
+
+    impl<'a> From<&'a str> for Cow<'a, str> {
+        fn from(s: &'a str) -> Self {
+            Cow::Borrowed(s)
+        }
+    }
+
+    impl Deref for Cow<'_, str> {
+        type Target = str;
+        fn deref(&self) -> &str {
+            match self {
+                Cow::Borrowed(s) => s,
+                Cow::Owned(s) => s,
+            }
+        }
+    }
+
+    impl AsRef<str> for Cow<'_, str> {
+        fn as_ref(&self) -> &str {
+            self
+        }
+    }
+
+    impl PartialEq<str> for Cow<'_, str> {
+        fn eq(&self, other: &str) -> bool {
+            **self == *other
+        }
+    }
+
+    impl PartialEq<&str> for Cow<'_, str> {
+        fn eq(&self, other: &&str) -> bool {
+            **self == **other
+        }
+    }
+
+    impl fmt::Display for Cow<'_, str> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self)
+        }
+    }
+
+    /// Writes into a [`String`], dropping whatever does not fit.
+    pub(crate) struct Truncate<'a>(pub(crate) &'a mut String);
+
+    impl Write for Truncate<'_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            for c in s.chars() {
+                if self.0.push(c).is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Stand-in for `alloc::string::ToString`, truncating at [`STRING_CAPACITY`].
+    pub(crate) trait ToString {
+        fn to_string(&self) -> String;
+    }
+
+    impl<T: fmt::Display + ?Sized> ToString for T {
+        fn to_string(&self) -> String {
+            let mut s = String::new();
+            let _ = write!(Truncate(&mut s), "{}", self);
+            s
+        }
+    }
+
+    /// Stand-in for `alloc::format!`, truncating at [`STRING_CAPACITY`].
+    macro_rules! format {
+        ($($arg:tt)*) => {{
+            let mut s = $crate::storage::String::new();
+            let _ = core::fmt::Write::write_fmt(
+                &mut $crate::storage::Truncate(&mut s),
+                format_args!($($arg)*),
+            );
+            s
+        }};
+    }
+    pub(crate) use format;
+}
+
+#[cfg(all(test, not(feature = "alloc"), feature = "all-sentences"))]
+mod tests {
+    use super::*;
+    use crate::{framer::Framer, sentences::NmeaSentence, NmeaParser, Sentence};
+
+    #[test]
+    fn test_decode_without_allocator() {
+        let mut framer = Framer::new();
+        framer.push(b"$WIMWV,049,R,000.03,N,A*03\r\n");
+        framer.push(
+            b"$GPGGA,113342.000,5045.7837,N,00132.4127,W,1,06,1.3,-10.2,M,47.8,M,,0000*56\r\n",
+        );
+        framer.push(b"$WIXDR,C,+023.1,C,TEMP,P,0.9989,B,PRESS,H,040,P,RH*25\r\n");
+        framer.push(b"$HEHDT,238.5,T\r\n");
+        framer.push(b"$PSVDY,-0.210,-0.116,9.825,-0.0044,0.0011,-0.0044,217.3,0.6,-1.4,0.021,-0.012,9.828,703*6C\r\n");
+
+        assert!(matches!(framer.next_sentence(), Some(Ok(Sentence::Mwv(_)))));
+        assert!(matches!(framer.next_sentence(), Some(Ok(Sentence::Gga(_)))));
+        match framer.next_sentence() {
+            Some(Ok(Sentence::Xdr(xdr))) => {
+                assert_eq!(xdr.readings.len(), 3);
+                assert_eq!(xdr.message_id, "XDR");
+            }
+            other => panic!("Expected XDR, got {:?}", other),
+        }
+        assert!(matches!(framer.next_sentence(), Some(Ok(Sentence::Hdt(_)))));
+        assert!(matches!(
+            framer.next_sentence(),
+            Some(Ok(Sentence::Svdy(_)))
+        ));
+        assert!(framer.next_sentence().is_none());
+    }
+
+    #[test]
+    fn test_fixed_capacity() {
+        let line = "$GPTXT,01,01,02,ALARM^21 ^5EHIGH^5E TEMP*41";
+        let nmea = NmeaParser::to_nmea(line).unwrap();
+        assert_eq!(nmea.fields[3], "ALARM! ^HIGH^ TEMP");
+        assert_eq!(nmea.to_string(), line);
+
+        let commas = [b','; 40];
+        let mut line = String::new();
+        line.push_str("$GPXXX").unwrap();
+        line.push_str(core::str::from_utf8(&commas).unwrap())
+            .unwrap();
+        assert!(matches!(
+            NmeaParser::to_nmea(&line),
+            Err(ParseNMEA0183Error::CapacityExceeded(VEC_CAPACITY))
+        ));
+
+        let quadruple = ["C", "+023.1", "C", "TEMP"];
+        let fields = [quadruple; VEC_CAPACITY + 1];
+        assert!(matches!(
+            crate::sentences::xdr::to_readings(fields.as_flattened()),
+            Err(ParseNMEA0183Error::CapacityExceeded(VEC_CAPACITY))
+        ));
+
+        // Nine readings decode, but their 36 fields do not fit into a sentence again.
+        let fields = [quadruple; 9];
+        let xdr = crate::sentences::xdr::Xdr {
+            talker_id: "WI".parse().unwrap(),
+            message_id: Cow::Borrowed("XDR"),
+            tag_block: None,
+            readings: crate::sentences::xdr::to_readings(fields.as_flattened()).unwrap(),
+        };
+        assert!(matches!(
+            xdr.encode(&Default::default()),
+            Err(ParseNMEA0183Error::CapacityExceeded(VEC_CAPACITY))
+        ));
+
+        let long = [b'x'; 100];
+        let long = core::str::from_utf8(&long).unwrap();
+        assert_eq!(long.to_string(), long[..STRING_CAPACITY]);
+    }
+
+    #[test]
+    fn test_framer_drops_oldest_bytes() {
+        let mut framer = Framer::new();
+        framer.push(&[b'#'; BUFFER_CAPACITY + 100]);
+        framer.push(b"$WIMWV,049,R,000.03,N,A*03\r\n");
+        assert!(matches!(framer.next_sentence(), Some(Ok(Sentence::Mwv(_)))));
+        assert_eq!(framer.discarded_bytes(), BUFFER_CAPACITY + 100);
+    }
+}
@@ -0,0 +1,818 @@
+//! [`proptest`] strategies generating sentences, for property testing code that reads or
+//! writes them.
+//!
+//! There is a strategy for every sentence struct of the enabled sentence families, such as
+//! [`mwv`] and [`gga`], and [`sentence`] picks any of them.  The generated sentences are the
+//! ones a decoder returns: every line [`encoded`] from them parses back into the same sentence.
+//! Numbers have a few decimals, as instruments write them, and text fields may hold any
+//! character, including the reserved ones that are written as `^HH` escapes.
+//!
+//! [`corrupted`] breaks encoded lines the way a noisy link does, see [`Corruption`], and
+//! [`nmea`] generates the sentences of the [`crate::catalogue`] with arbitrary fields, for
+//! testing decoders on input that splits but is not valid.
+//!
+//! ```
+//! use oor_nmea0183::{
+//!     sentences::error::ParseNMEA0183Error,
+//!     strategies::{corrupted_line, line, Corruption},
+//!     NmeaParser,
+//! };
+//! use proptest::{prop_assert, proptest};
+//!
+//! proptest!(|(line in line())| {
+//!     prop_assert!(NmeaParser::parse(&line).is_ok());
+//! });
+//! proptest!(|((corruption, line) in corrupted_line())| {
+//!     let result = NmeaParser::parse(&line);
+//!     if corruption == Corruption::BadChecksum {
+//!         prop_assert!(matches!(result, Err(ParseNMEA0183Error::NMEA0183ChecksumError)));
+//!     }
+//! });
+//! ```
+
+use proptest::{collection::vec, option, prelude::*, sample::select, strategy::Union};
+
+#[cfg(feature = "gnss")]
+use crate::sentences::gga::{FixQuality, Gga, Hemisphere};
+#[cfg(feature = "gill")]
+use crate::sentences::pgilt::{Gilt, ZOrientation};
+#[cfg(feature = "seaview")]
+use crate::sentences::seaview::{
+    psvdy::Svdy,
+    psvsd::Svsd,
+    psvsh::Svsh,
+    psvsi::Svsi,
+    psvss::{SpectrumBin, Svss},
+    psvst::Svst,
+    psvsv::Svsv,
+    psvsw::Svsw,
+};
+#[cfg(feature = "transducer")]
+use crate::sentences::xdr::Xdr;
+#[cfg(feature = "heading")]
+use crate::sentences::{hdm::Hdm, hdm::HeadingType, hdt::Hdt};
+#[cfg(feature = "wind")]
+use crate::sentences::{
+    mwv::{AcceptableMeasurement, Mwv, WindDirectionType},
+    UnitsOfSpeed,
+};
+#[cfg(any(feature = "transducer", feature = "gill"))]
+use crate::sentences::{Reading, TransducerReading, UnitsOfMeasurement};
+use crate::{
+    catalogue,
+    config::EncodeOptions,
+    sentences::query::Query,
+    storage::{Cow, String, ToString, Vec},
+    tag_block::{SentenceGroup, TagBlock},
+    talker::TalkerId,
+    Delimiter, Nmea, NmeaSentence, Sentence,
+};
+#[cfg(feature = "seaview")]
+use uom::si::{
+    acceleration::meter_per_second_squared,
+    angle::degree,
+    angular_velocity::degree_per_second,
+    f32::{Acceleration, Angle, AngularVelocity, Length, Time},
+    length::meter,
+    time::second,
+};
+
+/// A talker ID of a standard sentence, one of the known ones or [`TalkerId::Other`].
+pub fn talker_id() -> impl Strategy<Value = TalkerId> {
+    "[A-OQ-Z][A-Z0-9]".prop_map(|talker_id| talker_id.parse().unwrap())
+}
+
+fn proprietary(manufacturer: &str) -> TalkerId {
+    TalkerId::Proprietary {
+        manufacturer: manufacturer.parse().unwrap(),
+    }
+}
+
+/// A TAG block with at least one parameter, or none.
+pub fn tag_block() -> impl Strategy<Value = Option<TagBlock>> {
+    let value = || option::of(text());
+    let group = (1..10u32, 1..10u32, any::<u32>()).prop_map(|(line, total, id)| SentenceGroup {
+        line,
+        total,
+        id,
+    });
+    option::of(
+        (
+            option::of(any::<i64>()),
+            value(),
+            option::of(group),
+            option::of(any::<u32>()),
+            option::of(any::<i64>()),
+            value(),
+            value(),
+            vec(("[A-Z][A-Za-z0-9]{0,2}", text()), 0..3),
+        )
+            .prop_map(
+                |(
+                    unix_time,
+                    destination,
+                    group,
+                    line_count,
+                    relative_time,
+                    source,
+                    text,
+                    other,
+                )| {
+                    TagBlock {
+                        unix_time,
+                        destination,
+                        group,
+                        line_count,
+                        relative_time,
+                        source,
+                        text,
+                        other,
+                    }
+                },
+            )
+            .prop_filter("a TAG block needs a parameter", |tag_block| {
+                *tag_block != TagBlock::default()
+            }),
+    )
+}
+
+/// Any text without control characters.
+pub fn text() -> impl Strategy<Value = String> {
+    "\\PC{0,16}"
+}
+
+/// A number with up to `decimals` decimals, `mantissa` divided by a power of ten.
+#[cfg_attr(
+    not(any(
+        feature = "gnss",
+        feature = "wind",
+        feature = "heading",
+        feature = "transducer",
+        feature = "gill",
+        feature = "seaview"
+    )),
+    allow(dead_code)
+)]
+fn decimal(mantissa: impl Strategy<Value = i32>, decimals: u32) -> impl Strategy<Value = f32> {
+    (mantissa, 0..=decimals)
+        .prop_map(|(mantissa, decimals)| mantissa as f32 / 10f32.powi(decimals as i32))
+}
+
+/// A time of day as written in `hhmmss.ss` fields.
+#[cfg(feature = "gnss")]
+fn time_of_day() -> impl Strategy<Value = f32> {
+    (0..24i32, 0..60i32, 0..60i32, 0..=3u32).prop_flat_map(|(h, m, s, decimals)| {
+        let whole = h * 10_000 + m * 100 + s;
+        (0..10i32.pow(decimals)).prop_map(move |fraction| {
+            (whole * 10i32.pow(decimals) + fraction) as f32 / 10f32.powi(decimals as i32)
+        })
+    })
+}
+
+/// A latitude or longitude as written in `ddmm.mmmm` and `dddmm.mmmm` fields.
+#[cfg(feature = "gnss")]
+fn position(degrees: i64) -> impl Strategy<Value = f64> {
+    (0..degrees, 0..600_000i64)
+        .prop_map(|(degrees, minutes)| (degrees * 1_000_000 + minutes) as f64 / 10_000.0)
+}
+
+#[cfg(feature = "wind")]
+pub fn mwv() -> impl Strategy<Value = Mwv> {
+    (
+        talker_id(),
+        tag_block(),
+        option::of(0..360i32),
+        prop_oneof![
+            Just(WindDirectionType::Relative),
+            Just(WindDirectionType::True)
+        ],
+        option::of(decimal(0..100_000, 2)),
+        option::of(prop_oneof![
+            Just(UnitsOfSpeed::KilometersPerHour),
+            Just(UnitsOfSpeed::MetresPerSecond),
+            Just(UnitsOfSpeed::Knots),
+            Just(UnitsOfSpeed::StatuteMilesPerHour),
+        ]),
+        prop_oneof![
+            Just(AcceptableMeasurement::Acceptable),
+            Just(AcceptableMeasurement::Void)
+        ],
+    )
+        .prop_map(
+            |(
+                talker_id,
+                tag_block,
+                wind_direction_deg,
+                wind_dir_type,
+                wind_speed,
+                wind_speed_units,
+                acceptable,
+            )| Mwv {
+                talker_id,
+                message_id: Cow::Borrowed("MWV"),
+                tag_block,
+                wind_direction_deg,
+                wind_dir_type: Some(wind_dir_type),
+                wind_speed,
+                wind_speed_units,
+                acceptable: Some(acceptable),
+            },
+        )
+}
+
+#[cfg(feature = "gnss")]
+pub fn gga() -> impl Strategy<Value = Gga> {
+    let hemisphere = |a, b| option::of(prop_oneof![Just(a), Just(b)]);
+    let fix_quality = prop_oneof![
+        Just(FixQuality::FixNotValid),
+        Just(FixQuality::GpsFix),
+        Just(FixQuality::DifferentialGpsFix),
+        Just(FixQuality::NotApplicable),
+        Just(FixQuality::RtkFixed),
+        Just(FixQuality::RtkFloat),
+        Just(FixQuality::InsDeadReckoning),
+    ];
+    (
+        (talker_id(), tag_block(), option::of(time_of_day())),
+        (
+            option::of(position(90)),
+            hemisphere(Hemisphere::North, Hemisphere::South),
+            option::of(position(180)),
+            hemisphere(Hemisphere::East, Hemisphere::West),
+        ),
+        (
+            fix_quality,
+            option::of(0..32u32),
+            option::of(decimal(0..1000, 1)),
+        ),
+        (
+            option::of(decimal(-10_000..100_000, 1)),
+            option::of(decimal(-1_000..1_000, 1)),
+            option::of(decimal(0..1_000, 1)),
+            option::of(0..1024u32),
+        ),
+    )
+        .prop_map(
+            |(
+                (talker_id, tag_block, fix_time),
+                (latitude, latitude_hemisphere, longitude, longitude_hemisphere),
+                (fix_quality, num_satellites, hdop),
+                (altitude_msl, geoid_separation, differential_age, station_id),
+            )| Gga {
+                talker_id,
+                message_id: Cow::Borrowed("GGA"),
+                tag_block,
+                fix_time,
+                latitude,
+                latitude_hemisphere,
+                longitude,
+                longitude_hemisphere,
+                fix_quality: Some(fix_quality),
+                num_satellites,
+                hdop,
+                altitude_msl,
+                geoid_separation,
+                differential_age,
+                differential_gps_reference_station_id: station_id,
+            },
+        )
+}
+
+#[cfg(feature = "heading")]
+fn heading() -> impl Strategy<Value = (TalkerId, Option<TagBlock>, Option<f32>, Option<HeadingType>)>
+{
+    (
+        talker_id(),
+        tag_block(),
+        option::of(decimal(0..3600, 1)),
+        option::of(prop_oneof![
+            Just(HeadingType::Magentic),
+            Just(HeadingType::True)
+        ]),
+    )
+}
+
+#[cfg(feature = "heading")]
+pub fn hdm() -> impl Strategy<Value = Hdm> {
+    heading().prop_map(|(talker_id, tag_block, heading, heading_type)| Hdm {
+        talker_id,
+        message_id: Cow::Borrowed("HDM"),
+        tag_block,
+        heading,
+        heading_type,
+    })
+}
+
+#[cfg(feature = "heading")]
+pub fn hdt() -> impl Strategy<Value = Hdt> {
+    heading().prop_map(|(talker_id, tag_block, heading, heading_type)| Hdt {
+        talker_id,
+        message_id: Cow::Borrowed("HDT"),
+        tag_block,
+        heading,
+        heading_type,
+    })
+}
+
+#[cfg(feature = "transducer")]
+fn units_of_measurement() -> impl Strategy<Value = UnitsOfMeasurement> {
+    select(
+        &[
+            UnitsOfMeasurement::Amperes,
+            UnitsOfMeasurement::Bar,
+            UnitsOfMeasurement::Celsius,
+            UnitsOfMeasurement::Degrees,
+            UnitsOfMeasurement::Hertz,
+            UnitsOfMeasurement::Kelvin,
+            UnitsOfMeasurement::LitresPerSecond,
+            UnitsOfMeasurement::Meter,
+            UnitsOfMeasurement::Newton,
+            UnitsOfMeasurement::PartsPerThousand,
+            UnitsOfMeasurement::Percent,
+            UnitsOfMeasurement::Volts,
+        ][..],
+    )
+}
+
+/// A reading of an `XDR` sentence, which always has a name, empty if it was not sent.
+#[cfg(feature = "transducer")]
+fn transducer_reading() -> impl Strategy<Value = TransducerReading> {
+    (
+        "[A-Z]",
+        option::of(decimal(-100_000..100_000, 4)),
+        option::of(units_of_measurement()),
+        text(),
+    )
+        .prop_map(|(kind, reading, units, name)| {
+            let reading = Reading {
+                reading,
+                units,
+                name: Some(name),
+            };
+            TransducerReading::from_nmea(&kind, reading).expect("the type is not empty")
+        })
+}
+
+#[cfg(feature = "transducer")]
+pub fn xdr() -> impl Strategy<Value = Xdr> {
+    (
+        talker_id(),
+        tag_block(),
+        vec(option::of(transducer_reading()), 1..5),
+    )
+        .prop_map(|(talker_id, tag_block, readings)| Xdr {
+            talker_id,
+            message_id: Cow::Borrowed("XDR"),
+            tag_block,
+            readings,
+        })
+}
+
+#[cfg(feature = "gill")]
+pub fn gilt() -> impl Strategy<Value = Gilt> {
+    let tilt = || {
+        option::of(decimal(-900..=900, 1)).prop_map(|reading| {
+            Some(TransducerReading::AngularDisplacement(Reading {
+                reading,
+                units: Some(UnitsOfMeasurement::Degrees),
+                name: None,
+            }))
+        })
+    };
+    (
+        tag_block(),
+        tilt(),
+        tilt(),
+        prop_oneof![
+            Just(ZOrientation::FacingUpright),
+            Just(ZOrientation::FacingDownwards)
+        ],
+        text(),
+    )
+        .prop_map(
+            |(tag_block, x_tilt, y_tilt, z_orientation, sensor_name)| Gilt {
+                talker_id: proprietary("GIL"),
+                message_id: Cow::Borrowed("T"),
+                tag_block,
+                x_tilt,
+                y_tilt,
+                z_orientation: Some(z_orientation),
+                sensor_name: Some(sensor_name),
+            },
+        )
+}
+
+/// An angle that is written in degrees, but stored in radians.  Only angles that convert back
+/// to the same number of degrees are generated.
+#[cfg(feature = "seaview")]
+fn angle() -> impl Strategy<Value = Angle> {
+    decimal(-3600..=3600, 1).prop_filter_map("lost in the conversion to radians", |degrees| {
+        let angle = Angle::new::<degree>(degrees);
+        (angle.get::<degree>() == degrees).then_some(angle)
+    })
+}
+
+/// Like [`angle`], for angular velocities written in degrees per second.
+#[cfg(feature = "seaview")]
+fn angular_velocity() -> impl Strategy<Value = AngularVelocity> {
+    decimal(-10_000..=10_000, 2).prop_filter_map(
+        "lost in the conversion to radians per second",
+        |degrees| {
+            let velocity = AngularVelocity::new::<degree_per_second>(degrees);
+            (velocity.get::<degree_per_second>() == degrees).then_some(velocity)
+        },
+    )
+}
+
+#[cfg(feature = "seaview")]
+fn acceleration() -> impl Strategy<Value = Acceleration> {
+    decimal(-10_000..=10_000, 3).prop_map(Acceleration::new::<meter_per_second_squared>)
+}
+
+#[cfg(feature = "seaview")]
+pub fn svdy() -> impl Strategy<Value = Svdy> {
+    let acceleration = || option::of(acceleration());
+    let angular_velocity = || option::of(angular_velocity());
+    let angle = || option::of(angle());
+    (
+        (tag_block(), option::of(any::<u32>())),
+        (acceleration(), acceleration(), acceleration()),
+        (angular_velocity(), angular_velocity(), angular_velocity()),
+        (angle(), angle(), angle()),
+        (acceleration(), acceleration(), acceleration()),
+    )
+        .prop_map(
+            |(
+                (tag_block, index),
+                (acc_x, acc_y, acc_z),
+                (gyr_p, gyr_q, gyr_r),
+                (heading, pitch, roll),
+                (acc_n, acc_e, acc_u),
+            )| Svdy {
+                talker_id: proprietary("SVD"),
+                message_id: Cow::Borrowed("Y"),
+                tag_block,
+                acc_x,
+                acc_y,
+                acc_z,
+                gyr_p,
+                gyr_q,
+                gyr_r,
+                heading,
+                pitch,
+                roll,
+                acc_n,
+                acc_e,
+                acc_u,
+                index,
+            },
+        )
+}
+
+#[cfg(feature = "seaview")]
+pub fn svss() -> impl Strategy<Value = Svss> {
+    (tag_block(), vec(decimal(-100_000..100_000, 4), 14)).prop_map(|(tag_block, values)| Svss {
+        talker_id: proprietary("SVS"),
+        message_id: Cow::Borrowed("S"),
+        tag_block,
+        bin: SpectrumBin {
+            period: values[0],
+            es_mag: values[1],
+            n_re: values[2],
+            n_im: values[3],
+            e_re: values[4],
+            e_im: values[5],
+            u_re: values[6],
+            u_im: values[7],
+            u_mag: values[8],
+            theta: values[9],
+            a1: values[10],
+            b1: values[11],
+            a2: values[12],
+            b2: values[13],
+        },
+    })
+}
+
+#[cfg(feature = "seaview")]
+pub fn svsd() -> impl Strategy<Value = Svsd> {
+    (
+        tag_block(),
+        decimal(0..100_000, 3),
+        vec(decimal(0..100_000, 4), 1..16),
+    )
+        .prop_map(|(tag_block, period, esmag)| Svsd {
+            talker_id: proprietary("SVS"),
+            message_id: Cow::Borrowed("D"),
+            tag_block,
+            period,
+            esmag,
+        })
+}
+
+/// Timestamps are written in whole seconds with a four digit year.
+#[cfg(feature = "seaview")]
+pub fn svst() -> impl Strategy<Value = Svst> {
+    let timestamp = (0..4_102_444_800i64).prop_map(|seconds| {
+        chrono::DateTime::from_timestamp(seconds, 0)
+            .unwrap()
+            .naive_utc()
+    });
+    (tag_block(), option::of(timestamp)).prop_map(|(tag_block, timestamp)| Svst {
+        talker_id: proprietary("SVS"),
+        message_id: Cow::Borrowed("T"),
+        tag_block,
+        timestamp,
+    })
+}
+
+#[cfg(feature = "seaview")]
+pub fn svsi() -> impl Strategy<Value = Svsi> {
+    (tag_block(), text()).prop_map(|(tag_block, identity)| Svsi {
+        talker_id: proprietary("SVS"),
+        message_id: Cow::Borrowed("I"),
+        tag_block,
+        identity,
+    })
+}
+
+#[cfg(feature = "seaview")]
+pub fn svsv() -> impl Strategy<Value = Svsv> {
+    let length = || option::of(decimal(-100_000..=100_000, 3).prop_map(Length::new::<meter>));
+    (
+        tag_block(),
+        option::of(decimal(0..1_000_000, 3).prop_map(Time::new::<second>)),
+        length(),
+        length(),
+        length(),
+    )
+        .prop_map(|(tag_block, time, north, east, up)| Svsv {
+            talker_id: proprietary("SVS"),
+            message_id: Cow::Borrowed("V"),
+            tag_block,
+            time,
+            north,
+            east,
+            up,
+        })
+}
+
+#[cfg(feature = "seaview")]
+pub fn svsh() -> impl Strategy<Value = Svsh> {
+    (tag_block(), vec(text(), 1..8)).prop_map(|(tag_block, headers)| Svsh {
+        talker_id: proprietary("SVS"),
+        message_id: Cow::Borrowed("H"),
+        tag_block,
+        headers,
+    })
+}
+
+#[cfg(feature = "seaview")]
+pub fn svsw() -> impl Strategy<Value = Svsw> {
+    (tag_block(), vec(text(), 1..8)).prop_map(|(tag_block, params)| Svsw {
+        talker_id: proprietary("SVS"),
+        message_id: Cow::Borrowed("W"),
+        tag_block,
+        params,
+    })
+}
+
+pub fn query() -> impl Strategy<Value = Query> {
+    (talker_id(), talker_id(), "[A-Z]{3}", tag_block()).prop_map(
+        |(requester, target, message_id, tag_block)| Query {
+            requester,
+            target,
+            message_id,
+            tag_block,
+        },
+    )
+}
+
+/// Any sentence of the enabled sentence families, or a query.
+pub fn sentence() -> impl Strategy<Value = Sentence> {
+    #[allow(unused_mut)]
+    let mut sentences = vec![query().prop_map(Sentence::Query).boxed()];
+    #[cfg(feature = "wind")]
+    sentences.push(mwv().prop_map(Sentence::Mwv).boxed());
+    #[cfg(feature = "gnss")]
+    sentences.push(gga().prop_map(Sentence::Gga).boxed());
+    #[cfg(feature = "transducer")]
+    sentences.push(xdr().prop_map(Sentence::Xdr).boxed());
+    #[cfg(feature = "heading")]
+    sentences.extend([
+        hdm().prop_map(Sentence::Hdm).boxed(),
+        hdt().prop_map(Sentence::Hdt).boxed(),
+    ]);
+    #[cfg(feature = "gill")]
+    sentences.push(gilt().prop_map(Sentence::Gilt).boxed());
+    #[cfg(feature = "seaview")]
+    sentences.extend([
+        svdy().prop_map(Sentence::Svdy).boxed(),
+        svss().prop_map(Sentence::Svss).boxed(),
+        svsd().prop_map(Sentence::Svsd).boxed(),
+        svst().prop_map(Sentence::Svst).boxed(),
+        svsi().prop_map(Sentence::Svsi).boxed(),
+        svsv().prop_map(Sentence::Svsv).boxed(),
+        svsh().prop_map(Sentence::Svsh).boxed(),
+        svsw().prop_map(Sentence::Svsw).boxed(),
+    ]);
+    Union::new(sentences)
+}
+
+/// The lines of the sentences of `sentences`, encoded with the default [`EncodeOptions`]: with
+/// a checksum and a `<CR><LF>` line ending.
+pub fn encoded<S>(sentences: impl Strategy<Value = S>) -> impl Strategy<Value = String>
+where
+    S: NmeaSentence + core::fmt::Debug,
+{
+    // Encoding only fails without an allocator, which the strategies need anyway.
+    sentences.prop_map(|sentence| {
+        sentence
+            .encode(&EncodeOptions::default())
+            .expect("encodes with an allocator")
+            .to_string()
+    })
+}
+
+/// The line of any sentence, see [`sentence`].
+pub fn line() -> impl Strategy<Value = String> {
+    encoded(sentence())
+}
+
+/// How [`corrupted`] breaks a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corruption {
+    /// The checksum does not match the sentence, which fails with
+    /// [`crate::sentences::error::ParseNMEA0183Error::NMEA0183ChecksumError`].
+    BadChecksum,
+    /// Fields are cut off the end of the sentence, with a checksum that matches what is left.
+    /// Whether the rest still decodes depends on the sentence.
+    TruncatedFields,
+    /// A character that cannot appear in a sentence, such as a control character or a
+    /// non-ASCII one, is inserted into the fields.  Like the grammar, the parser ends the
+    /// sentence before it, so the checksum is lost and a parser that requires one fails with
+    /// [`crate::sentences::error::ParseNMEA0183Error::MissingChecksum`].
+    IllegalCharacter,
+}
+
+/// Characters that are not allowed in the fields of a sentence, unescaped.
+const ILLEGAL_CHARACTERS: &[char] = &[
+    '\0', '\x07', '\t', '\x1b', '\x7f', '!', '$', '\\', '°', 'é', '€',
+];
+
+/// Lines of `lines`, encoded with a checksum, broken in one of the ways of [`Corruption`].
+pub fn corrupted(
+    lines: impl Strategy<Value = String>,
+) -> impl Strategy<Value = (Corruption, String)> {
+    (
+        lines,
+        select(
+            &[
+                Corruption::BadChecksum,
+                Corruption::TruncatedFields,
+                Corruption::IllegalCharacter,
+            ][..],
+        ),
+        any::<prop::sample::Index>(),
+        select(ILLEGAL_CHARACTERS),
+        1..=u8::MAX,
+    )
+        .prop_map(|(line, corruption, index, character, delta)| {
+            let line = corrupt(&line, corruption, index, character, delta);
+            (corruption, line)
+        })
+}
+
+/// The line of any sentence, see [`sentence`], broken in one of the ways of [`Corruption`].
+pub fn corrupted_line() -> impl Strategy<Value = (Corruption, String)> {
+    corrupted(line())
+}
+
+fn corrupt(
+    line: &str,
+    corruption: Corruption,
+    index: prop::sample::Index,
+    character: char,
+    delta: u8,
+) -> String {
+    let star = line.rfind('*').expect("an encoded line has a checksum");
+    let (body, checksum) = line.split_at(star);
+    let checksum = u8::from_str_radix(&checksum[1..3], 16).expect("a hex checksum");
+    let ending = &line[star + 3..];
+    // The sentence itself, after the TAG block if there is one.
+    let start = body
+        .find(['$', '!'])
+        .expect("an encoded line has a start delimiter")
+        + 1;
+    let first_comma = start
+        + body[start..]
+            .find(',')
+            .expect("the address ends with a comma");
+    match corruption {
+        Corruption::BadChecksum => {
+            format!("{}*{:02X}{}", body, checksum ^ delta, ending)
+        }
+        Corruption::TruncatedFields => {
+            let commas: Vec<usize> = body[start..]
+                .match_indices(',')
+                .map(|(i, _)| start + i)
+                .collect();
+            let body = &body[..*index.get(&commas)];
+            format!("{}*{:02X}{}", body, crate::checksum(&body[start..]), ending)
+        }
+        Corruption::IllegalCharacter => {
+            let at = first_comma + 1 + index.index(body.len() - first_comma);
+            let mut body = body.to_string();
+            body.insert(at, character);
+            format!("{}*{:02X}{}", body, crate::checksum(&body[start..]), ending)
+        }
+    }
+}
+
+/// A field of [`nmea`]: empty, a number, a letter or any text.
+fn nmea_field() -> impl Strategy<Value = Cow<'static, str>> {
+    prop_oneof![
+        select(vec!["", "-1", "+1"]).prop_map(ToString::to_string),
+        "[A-Z]",
+        "[+-]?[0-9]{1,6}(\\.[0-9]{0,4})?",
+        "[0-9]{4}-[0-9]{2}-[0-9]{2} [0-9]{2}:[0-9]{2}:[0-9]{2}",
+        text(),
+    ]
+    .prop_map(Cow::Owned)
+}
+
+/// The sentences listed in the [`crate::catalogue`], with their own address but any number of
+/// arbitrary fields.  They split into a valid [`Nmea`], but may not decode.
+pub fn nmea() -> impl Strategy<Value = Nmea<'static>> {
+    (
+        select(catalogue::sentences()),
+        talker_id(),
+        tag_block(),
+        vec(nmea_field(), 0..20),
+    )
+        .prop_map(|(info, talker_id, tag_block, fields)| Nmea {
+            tag_block,
+            delimiter: Delimiter::Parametric,
+            talker_id: info.manufacturer.map_or(talker_id, proprietary),
+            message_id: Cow::Borrowed(info.message_id),
+            fields,
+        })
+}
+
+#[cfg(all(test, feature = "all-sentences"))]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{ChecksumPolicy, ParserConfig},
+        sentences::{error::ParseNMEA0183Error, DecodeContext, DecodeMode},
+        NmeaParser,
+    };
+
+    proptest! {
+        #[test]
+        fn test_parse_encoded_sentence(sentence in sentence()) {
+            let line = sentence.encode(&EncodeOptions::default())?.to_string();
+            prop_assert_eq!(NmeaParser::parse(&line)?, sentence);
+        }
+
+        #[test]
+        fn test_parse_corrupted_line((corruption, line) in corrupted_line()) {
+            let result = NmeaParser::parse(&line);
+            match corruption {
+                Corruption::BadChecksum => prop_assert!(
+                    matches!(result, Err(ParseNMEA0183Error::NMEA0183ChecksumError)),
+                    "{:?}",
+                    result
+                ),
+                Corruption::IllegalCharacter => {
+                    let parser = NmeaParser::with_config(ParserConfig {
+                        checksum: ChecksumPolicy::Required,
+                        ..Default::default()
+                    });
+                    let result = parser.parse_line(&line);
+                    prop_assert!(
+                        matches!(result, Err(ParseNMEA0183Error::MissingChecksum)),
+                        "{:?}",
+                        result
+                    )
+                }
+                Corruption::TruncatedFields => {
+                    let _ = NmeaParser::parse_lenient(&line);
+                }
+            }
+        }
+
+        #[test]
+        fn test_parse_any_text(line in "\\PC{0,80}") {
+            let _ = NmeaParser::parse(&line);
+            let _ = NmeaParser::parse_lenient(&line);
+        }
+
+        #[test]
+        fn test_decode_arbitrary_fields(nmea in nmea()) {
+            for mode in [DecodeMode::Strict, DecodeMode::Lenient] {
+                let _ = Sentence::decode(nmea.clone(), &mut DecodeContext::new(mode));
+            }
+        }
+    }
+}
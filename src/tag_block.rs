@@ -0,0 +1,338 @@
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, NaiveDateTime};
+
+use crate::{
+    escape::{unescape, write_escaped},
+    sentences::error::ParseNMEA0183Error,
+    storage::{List, String, ToString, TryPush},
+    ChecksumWriter,
+};
+use core::{
+    fmt::{self, Write},
+    str::FromStr,
+};
+
+/// NMEA0183 v4 TAG block, as written in front of a sentence by loggers and IEC 61162-450
+/// gateways.
+///
+/// ### Parameters:
+/// - `c`: `unix_time`, seconds since the UNIX epoch.
+/// - `d`: `destination`, identifier of the receiving station.
+/// - `g`: `group`, the sentence grouping (`line-total-id`) for multi-line messages.
+/// - `n`: `line_count`, a running line counter.
+/// - `r`: `relative_time`, a relative time stamp.
+/// - `s`: `source`, identifier of the sending station (e.g. `GP01`).
+/// - `t`: `text`, free text.
+///
+/// Parameters with any other alphanumeric code, such as the vendor specific ones of some
+/// gateways, are kept as code and value in the order they were sent, see [`TagBlock::other`].  Like fields, text values are
+/// written with `^HH` escapes for the reserved characters, `,`, `*`, `\`, `$`, `!` and the
+/// others.
+///
+/// ### Example:
+/// - `\s:GP01,c:1700000000*2C\$GPGGA,...`
+///
+/// Every TAG block carries its own `*hh` checksum over the parameters, which is verified when
+/// parsing and regenerated when the block is written with [`fmt::Display`].  A TAG block
+/// without any parameters is not written at all, as `\*00\` would not parse again.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TagBlock {
+    pub unix_time: Option<i64>,
+    pub destination: Option<String>,
+    pub group: Option<SentenceGroup>,
+    pub line_count: Option<u32>,
+    pub relative_time: Option<i64>,
+    pub source: Option<String>,
+    pub text: Option<String>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_other"))]
+    pub(crate) other: List<(String, String)>,
+}
+
+/// Position of a sentence within a group of sentences that belong together (`g:1-2-73874`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SentenceGroup {
+    pub line: u32,
+    pub total: u32,
+    pub id: u32,
+}
+
+impl TagBlock {
+    /// Parses the parameters of a TAG block, the text between the leading `\` and the `*` of
+    /// its checksum.  The checksum itself is verified by [`crate::NmeaParser`].
+    pub(crate) fn parse(params: &str) -> Result<Self, ParseNMEA0183Error> {
+        let mut tag_block = TagBlock::default();
+        for param in params.split(',') {
+            let Some((code, value)) = param.split_once(':') else {
+                return Err(ParseNMEA0183Error::ParseGrammarError);
+            };
+            let invalid = |_| ParseNMEA0183Error::InvalidTagParameter {
+                code: code.to_string(),
+                value: value.to_string(),
+            };
+            match code {
+                "c" => tag_block.unix_time = Some(value.parse::<i64>().map_err(invalid)?),
+                "d" => tag_block.destination = Some(unescape(value)?.into_owned()),
+                "g" => tag_block.group = Some(value.parse::<SentenceGroup>()?),
+                "n" => tag_block.line_count = Some(value.parse::<u32>().map_err(invalid)?),
+                "r" => tag_block.relative_time = Some(value.parse::<i64>().map_err(invalid)?),
+                "s" => tag_block.source = Some(unescape(value)?.into_owned()),
+                "t" => tag_block.text = Some(unescape(value)?.into_owned()),
+                code => tag_block.push_other(code, &unescape(value)?)?,
+            }
+        }
+        Ok(tag_block)
+    }
+
+    /// Whether the TAG block has no parameters, it is not written then.
+    pub fn is_empty(&self) -> bool {
+        *self == TagBlock::default()
+    }
+
+    /// The parameters with codes other than the ones above, as code and value.
+    pub fn other(&self) -> &[(String, String)] {
+        &self.other
+    }
+
+    /// Adds a parameter with a code other than the ones above.
+    ///
+    /// The code has to be alphanumeric, anything else could not be parsed back and is
+    /// rejected with [`ParseNMEA0183Error::InvalidTagParameter`].
+    pub fn push_other(&mut self, code: &str, value: &str) -> Result<(), ParseNMEA0183Error> {
+        if !is_other_code(code) {
+            return Err(ParseNMEA0183Error::InvalidTagParameter {
+                code: code.to_string(),
+                value: value.to_string(),
+            });
+        }
+        self.other.try_push((code.to_string(), value.to_string()))
+    }
+
+    /// The `c:` parameter as a UTC date and time.
+    #[cfg(feature = "chrono")]
+    pub fn timestamp(&self) -> Option<NaiveDateTime> {
+        DateTime::from_timestamp(self.unix_time?, 0).map(|time| time.naive_utc())
+    }
+
+    /// Writes the parameters separated by commas, without the enclosing `\` and checksum.
+    fn write_params<W: Write>(&self, w: &mut W) -> fmt::Result {
+        let mut separator = "";
+        let mut code = |w: &mut W, code: &str| {
+            let result = write!(w, "{}{}:", separator, code);
+            separator = ",";
+            result
+        };
+        if let Some(unix_time) = self.unix_time {
+            code(w, "c")?;
+            write!(w, "{}", unix_time)?;
+        }
+        if let Some(destination) = &self.destination {
+            code(w, "d")?;
+            write_escaped(w, destination)?;
+        }
+        if let Some(group) = &self.group {
+            code(w, "g")?;
+            write!(w, "{}-{}-{}", group.line, group.total, group.id)?;
+        }
+        if let Some(line_count) = self.line_count {
+            code(w, "n")?;
+            write!(w, "{}", line_count)?;
+        }
+        if let Some(relative_time) = self.relative_time {
+            code(w, "r")?;
+            write!(w, "{}", relative_time)?;
+        }
+        if let Some(source) = &self.source {
+            code(w, "s")?;
+            write_escaped(w, source)?;
+        }
+        if let Some(text) = &self.text {
+            code(w, "t")?;
+            write_escaped(w, text)?;
+        }
+        for (other, value) in &self.other {
+            code(w, other)?;
+            write_escaped(w, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `code` can be used for one of the [`TagBlock::other`] parameters.
+fn is_other_code(code: &str) -> bool {
+    !code.is_empty()
+        && code.bytes().all(|b| b.is_ascii_alphanumeric())
+        && !matches!(code, "c" | "d" | "g" | "n" | "r" | "s" | "t")
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_other<'de, D>(deserializer: D) -> Result<List<(String, String)>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::{de::Error, Deserialize};
+    let other = List::<(String, String)>::deserialize(deserializer)?;
+    match other.iter().find(|(code, _)| !is_other_code(code)) {
+        Some((code, _)) => Err(D::Error::custom(format_args!(
+            "invalid TAG block code {}",
+            code
+        ))),
+        None => Ok(other),
+    }
+}
+
+/// Parses the value of a `g:` parameter, failing with
+/// [`ParseNMEA0183Error::InvalidTagParameter`].
+impl FromStr for SentenceGroup {
+    type Err = ParseNMEA0183Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, '-');
+        let mut next = || {
+            parts
+                .next()
+                .and_then(|part| part.parse::<u32>().ok())
+                .ok_or_else(|| ParseNMEA0183Error::InvalidTagParameter {
+                    code: "g".to_string(),
+                    value: s.to_string(),
+                })
+        };
+        Ok(SentenceGroup {
+            line: next()?,
+            total: next()?,
+            id: next()?,
+        })
+    }
+}
+
+impl fmt::Display for TagBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return Ok(());
+        }
+        f.write_char('\\')?;
+        let mut params = ChecksumWriter::new(&mut *f);
+        self.write_params(&mut params)?;
+        let checksum = params.checksum();
+        write!(f, "*{:02X}\\", checksum)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tag_block() {
+        let tag_block = TagBlock::parse("g:1-2-73874,n:157036,s:r003669945,c:1241544035").unwrap();
+
+        assert_eq!(tag_block.unix_time, Some(1241544035));
+        assert_eq!(tag_block.line_count, Some(157036));
+        assert_eq!(tag_block.source, Some("r003669945".to_string()));
+        assert_eq!(
+            tag_block.group,
+            Some(SentenceGroup {
+                line: 1,
+                total: 2,
+                id: 73874
+            })
+        );
+        assert_eq!(tag_block.destination, None);
+    }
+
+    #[test]
+    fn test_parse_other_parameters() {
+        let tag_block = TagBlock::parse("s:GP01,x:foo,C:1700000000").unwrap();
+        assert_eq!(tag_block.source, Some("GP01".to_string()));
+        assert_eq!(tag_block.unix_time, None);
+        assert_eq!(
+            tag_block.other(),
+            [
+                ("x".to_string(), "foo".to_string()),
+                ("C".to_string(), "1700000000".to_string())
+            ]
+        );
+        assert_eq!(tag_block.to_string(), "\\s:GP01,x:foo,C:1700000000*04\\");
+    }
+
+    #[test]
+    fn test_parse_tag_block_errors() {
+        let invalid = |params| match TagBlock::parse(params) {
+            Err(ParseNMEA0183Error::InvalidTagParameter { code, value }) => (code, value),
+            other => panic!("Expected an invalid parameter, got {:?}", other),
+        };
+        assert_eq!(invalid("c:abc"), ("c".to_string(), "abc".to_string()));
+        assert_eq!(invalid("s:GP01,n:-1"), ("n".to_string(), "-1".to_string()));
+        assert_eq!(invalid("g:1-2"), ("g".to_string(), "1-2".to_string()));
+        assert_eq!(invalid("g:1-x-3"), ("g".to_string(), "1-x-3".to_string()));
+        assert!(matches!(
+            TagBlock::parse("s"),
+            Err(ParseNMEA0183Error::ParseGrammarError)
+        ));
+    }
+
+    #[test]
+    fn test_tag_block_round_trip() {
+        let mut tag_block = TagBlock {
+            destination: Some("$BRIDGE!".to_string()),
+            source: Some("a\\b".to_string()),
+            text: Some("a,b*c ^~ 25°C".to_string()),
+            ..Default::default()
+        };
+        tag_block.push_other("x", "1,2").unwrap();
+        let line = format!("{}$HEHDT,238.5,T", tag_block);
+        assert_eq!(
+            line,
+            "\\d:^24BRIDGE^21,s:a^5Cb,t:a^2Cb^2Ac ^5E^7E 25^C2^B0C,x:1^2C2*0D\\$HEHDT,238.5,T"
+        );
+        let nmea = crate::NmeaParser::to_nmea(&line).unwrap();
+        assert_eq!(nmea.tag_block, Some(tag_block));
+    }
+
+    #[test]
+    fn test_write_tag_block() {
+        let tag_block = TagBlock {
+            unix_time: Some(1700000000),
+            source: Some("GP01".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(tag_block.to_string(), "\\c:1700000000,s:GP01*2C\\");
+
+        let tag_block = TagBlock {
+            destination: Some("BRIDGE".to_string()),
+            relative_time: Some(1500),
+            text: Some("hello world".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            tag_block.to_string(),
+            "\\d:BRIDGE,r:1500,t:hello world*63\\"
+        );
+    }
+
+    #[test]
+    fn test_empty_tag_block_and_other_codes_round_trip() {
+        let nmea = crate::Nmea {
+            tag_block: Some(TagBlock::default()),
+            ..crate::NmeaParser::to_nmea("$HEHDT,238.5,T").unwrap()
+        };
+        let line = nmea.to_string();
+        assert!(line.starts_with("$HEHDT,238.5,T*"), "{}", line);
+        assert_eq!(crate::NmeaParser::to_nmea(&line).unwrap().tag_block, None);
+
+        let mut tag_block = TagBlock::default();
+        for code in ["x,y", "", "s", "a:b", "x*"] {
+            assert!(matches!(
+                tag_block.push_other(code, "1"),
+                Err(ParseNMEA0183Error::InvalidTagParameter { .. })
+            ));
+        }
+        assert!(tag_block.is_empty());
+        assert_eq!(tag_block.to_string(), "");
+
+        tag_block.push_other("x1", "a,b").unwrap();
+        let line = format!("{}$HEHDT,238.5,T", tag_block);
+        let nmea = crate::NmeaParser::to_nmea(&line).unwrap();
+        assert_eq!(nmea.tag_block, Some(tag_block));
+    }
+}
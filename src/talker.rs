@@ -0,0 +1,267 @@
+use core::{fmt, str::FromStr};
+
+use crate::{sentences::error::ParseNMEA0183Error, storage::ToString};
+
+/// The source of a sentence, taken from the first characters of its address field.
+///
+/// Proprietary sentences start with `P` followed by a three character manufacturer mnemonic,
+/// e.g. `$PGILT` is sentence `T` from manufacturer `GIL`.  Talker IDs that are not listed here
+/// are kept as [`TalkerId::Other`].
+///
+/// [`TalkerId::as_str`] returns the talker ID itself (`GP`, or `P` for proprietary sentences),
+/// while [`fmt::Display`] writes it as it appears in a sentence, manufacturer included.  Talker
+/// IDs compare equal to strings in their [`fmt::Display`] form, `"PGIL"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TalkerId {
+    /// `AG`, autopilot.
+    Autopilot,
+    /// `AI`, AIS transponder.
+    Ais,
+    /// `CC`, computer.
+    Computer,
+    /// `EC`, electronic chart display and information system.
+    Ecdis,
+    /// `GA`, Galileo.
+    Galileo,
+    /// `GB`, BeiDou.
+    BeiDou,
+    /// `GI`, NavIC.
+    NavIc,
+    /// `GL`, GLONASS.
+    Glonass,
+    /// `GN`, combined global navigation satellite systems.
+    Gnss,
+    /// `GP`, GPS.
+    Gps,
+    /// `GQ`, QZSS.
+    Qzss,
+    /// `HC`, magnetic compass.
+    MagneticCompass,
+    /// `HE`, north seeking gyro.
+    NorthSeekingGyro,
+    /// `HN`, non north seeking gyro.
+    NonNorthSeekingGyro,
+    /// `II`, integrated instrumentation.
+    IntegratedInstrumentation,
+    /// `IN`, integrated navigation.
+    IntegratedNavigation,
+    /// `SD`, depth sounder.
+    DepthSounder,
+    /// `VW`, mechanical water speed log.
+    WaterSpeedLog,
+    /// `WI`, weather instrument.
+    WeatherInstrument,
+    /// `YX`, transducer.
+    Transducer,
+    /// `P`, a proprietary sentence.
+    Proprietary { manufacturer: Manufacturer },
+    /// Any other two character talker ID.
+    Other(OtherTalkerId),
+}
+
+/// A two character talker ID without a [`TalkerId`] variant of its own, only built by parsing
+/// it so that known talker IDs are never kept as [`TalkerId::Other`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OtherTalkerId([u8; 2]);
+
+impl OtherTalkerId {
+    pub fn as_str(&self) -> &str {
+        // Only built from ASCII alphanumerics.
+        core::str::from_utf8(&self.0).unwrap_or_default()
+    }
+}
+
+/// The three character manufacturer mnemonic of a proprietary sentence, e.g. `GIL` for
+/// `$PGILT`, stored inline so that parsing a proprietary sentence does not allocate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Manufacturer([u8; 3]);
+
+impl Manufacturer {
+    pub fn as_str(&self) -> &str {
+        // Only built from ASCII alphanumerics.
+        core::str::from_utf8(&self.0).unwrap_or_default()
+    }
+}
+
+impl FromStr for Manufacturer {
+    type Err = ParseNMEA0183Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes() {
+            &[a, b, c] if s.bytes().all(|b| b.is_ascii_alphanumeric()) => {
+                Ok(Manufacturer([a, b, c]))
+            }
+            _ => Err(ParseNMEA0183Error::ConvertToEnumError(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Manufacturer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TalkerId {
+    /// The two character talker ID, or `P` for every proprietary sentence.  [`fmt::Display`]
+    /// and comparisons with strings use the form written in a sentence, which includes the
+    /// manufacturer, e.g. `PGIL`.
+    pub fn prefix(&self) -> &str {
+        match self {
+            TalkerId::Autopilot => "AG",
+            TalkerId::Ais => "AI",
+            TalkerId::Computer => "CC",
+            TalkerId::Ecdis => "EC",
+            TalkerId::Galileo => "GA",
+            TalkerId::BeiDou => "GB",
+            TalkerId::NavIc => "GI",
+            TalkerId::Glonass => "GL",
+            TalkerId::Gnss => "GN",
+            TalkerId::Gps => "GP",
+            TalkerId::Qzss => "GQ",
+            TalkerId::MagneticCompass => "HC",
+            TalkerId::NorthSeekingGyro => "HE",
+            TalkerId::NonNorthSeekingGyro => "HN",
+            TalkerId::IntegratedInstrumentation => "II",
+            TalkerId::IntegratedNavigation => "IN",
+            TalkerId::DepthSounder => "SD",
+            TalkerId::WaterSpeedLog => "VW",
+            TalkerId::WeatherInstrument => "WI",
+            TalkerId::Transducer => "YX",
+            TalkerId::Proprietary { .. } => "P",
+            TalkerId::Other(talker_id) => talker_id.as_str(),
+        }
+    }
+
+    /// The manufacturer mnemonic of a proprietary sentence.
+    pub fn manufacturer(&self) -> Option<&str> {
+        match self {
+            TalkerId::Proprietary { manufacturer } => Some(manufacturer.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn is_proprietary(&self) -> bool {
+        matches!(self, TalkerId::Proprietary { .. })
+    }
+}
+
+/// Parses a two character talker ID.  Proprietary talkers need their manufacturer and are
+/// built directly as [`TalkerId::Proprietary`].
+impl FromStr for TalkerId {
+    type Err = ParseNMEA0183Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "AG" => TalkerId::Autopilot,
+            "AI" => TalkerId::Ais,
+            "CC" => TalkerId::Computer,
+            "EC" => TalkerId::Ecdis,
+            "GA" => TalkerId::Galileo,
+            "GB" => TalkerId::BeiDou,
+            "GI" => TalkerId::NavIc,
+            "GL" => TalkerId::Glonass,
+            "GN" => TalkerId::Gnss,
+            "GP" => TalkerId::Gps,
+            "GQ" => TalkerId::Qzss,
+            "HC" => TalkerId::MagneticCompass,
+            "HE" => TalkerId::NorthSeekingGyro,
+            "HN" => TalkerId::NonNorthSeekingGyro,
+            "II" => TalkerId::IntegratedInstrumentation,
+            "IN" => TalkerId::IntegratedNavigation,
+            "SD" => TalkerId::DepthSounder,
+            "VW" => TalkerId::WaterSpeedLog,
+            "WI" => TalkerId::WeatherInstrument,
+            "YX" => TalkerId::Transducer,
+            s => match s.as_bytes() {
+                &[a, b] if s.bytes().all(|b| b.is_ascii_alphanumeric()) => {
+                    TalkerId::Other(OtherTalkerId([a, b]))
+                }
+                _ => return Err(ParseNMEA0183Error::ConvertToEnumError(s.to_string())),
+            },
+        })
+    }
+}
+
+impl fmt::Display for TalkerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            self.prefix(),
+            self.manufacturer().unwrap_or_default()
+        )
+    }
+}
+
+/// Serialized as written in a sentence, see [`fmt::Display`].
+#[cfg(feature = "serde")]
+impl serde::Serialize for TalkerId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TalkerId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = crate::storage::String::deserialize(deserializer)?;
+        match s.strip_prefix('P') {
+            Some(manufacturer) if s.len() > 2 => Ok(TalkerId::Proprietary {
+                manufacturer: manufacturer.parse().map_err(serde::de::Error::custom)?,
+            }),
+            _ => s.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl PartialEq<str> for TalkerId {
+    fn eq(&self, other: &str) -> bool {
+        match self.manufacturer() {
+            Some(manufacturer) => other.strip_prefix(self.prefix()) == Some(manufacturer),
+            None => self.prefix() == other,
+        }
+    }
+}
+
+impl PartialEq<&str> for TalkerId {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_talker_id() {
+        assert_eq!("GP".parse::<TalkerId>().unwrap(), TalkerId::Gps);
+        assert_eq!(
+            "WI".parse::<TalkerId>().unwrap(),
+            TalkerId::WeatherInstrument
+        );
+        assert!(matches!(
+            "ZZ".parse::<TalkerId>().unwrap(),
+            TalkerId::Other(other) if other.as_str() == "ZZ"
+        ));
+        assert_eq!("ZZ".parse::<TalkerId>().unwrap(), "ZZ");
+        assert!("GPS".parse::<TalkerId>().is_err());
+        assert!("G".parse::<TalkerId>().is_err());
+        assert!("GI".parse::<Manufacturer>().is_err());
+        assert!("G,L".parse::<Manufacturer>().is_err());
+
+        for talker_id in ["AI", "GN", "HC", "II", "YX", "ZZ"] {
+            assert_eq!(talker_id.parse::<TalkerId>().unwrap().prefix(), talker_id);
+        }
+
+        let talker_id = TalkerId::Proprietary {
+            manufacturer: "GIL".parse().unwrap(),
+        };
+        assert_eq!(talker_id, "PGIL");
+        assert_ne!(talker_id, "P");
+        assert_ne!(talker_id, "PGI");
+        assert_eq!(talker_id.manufacturer(), Some("GIL"));
+        assert_eq!(talker_id.to_string(), "PGIL");
+        assert_eq!(talker_id.prefix(), "P");
+        assert_eq!(TalkerId::Gps.to_string(), "GP");
+    }
+}